@@ -20,7 +20,16 @@ fn enum_toggles(toggles: &EnumToggles<TestToggles>) {
     black_box(toggles.get(TestToggles::Spades as usize));
 }
 
-fn list_toggles(list_toggles_value: &Vec<bool>) {
+fn enum_toggles_get_many(toggles: &EnumToggles<TestToggles>) {
+    black_box(toggles.get_many([
+        TestToggles::Hearts as usize,
+        TestToggles::Tiles as usize,
+        TestToggles::Pikes as usize,
+        TestToggles::Spades as usize,
+    ]));
+}
+
+fn list_toggles(list_toggles_value: &[bool]) {
     black_box(list_toggles_value[TestToggles::Hearts as usize]);
     black_box(list_toggles_value[TestToggles::Tiles as usize]);
     black_box(list_toggles_value[TestToggles::Pikes as usize]);
@@ -46,6 +55,12 @@ fn compare_methods(c: &mut Criterion) {
         |b, input| b.iter(|| enum_toggles(black_box(input))),
     );
 
+    group.bench_with_input(
+        BenchmarkId::new("Readonly-toggles", "enum_toggles_get_many"),
+        &toggles,
+        |b, input| b.iter(|| enum_toggles_get_many(black_box(input))),
+    );
+
     group.bench_with_input(
         BenchmarkId::new("Readonly-toggles", "List"),
         &list_toggles_value,
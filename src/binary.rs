@@ -0,0 +1,142 @@
+use crate::{EnumToggles, ToggleError};
+use serde::{Deserialize, Serialize};
+
+/// Wire layout of a binary snapshot.
+#[derive(Serialize, Deserialize)]
+struct BinarySnapshot {
+    fingerprint: u64,
+    generation: u64,
+    bits: Vec<u8>,
+}
+
+/// Compact binary serialization, far cheaper than yaml to cache or pass between processes.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Serialize the toggle values, the generation and the enum fingerprint with postcard.
+    ///
+    /// This operation is *O*(*n*).
+    #[cfg(feature = "postcard")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ToggleError> {
+        postcard::to_allocvec(&self.binary_snapshot())
+            .map_err(|e| ToggleError::Serialization(e.to_string()))
+    }
+
+    /// Create a new instance of `EnumToggles` from bytes produced by `to_bytes`, rejecting
+    /// snapshots of a different enum.
+    ///
+    /// This operation is *O*(*n*).
+    #[cfg(feature = "postcard")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ToggleError> {
+        let snapshot: BinarySnapshot =
+            postcard::from_bytes(bytes).map_err(|e| ToggleError::Serialization(e.to_string()))?;
+        Self::from_binary_snapshot(snapshot)
+    }
+
+    /// Serialize the toggle values, the generation and the enum fingerprint as CBOR, for
+    /// consumers in other languages.
+    ///
+    /// This operation is *O*(*n*).
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&self) -> Result<Vec<u8>, ToggleError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&self.binary_snapshot(), &mut bytes)
+            .map_err(|e| ToggleError::Serialization(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Create a new instance of `EnumToggles` from bytes produced by `to_cbor`, rejecting
+    /// snapshots of a different enum.
+    ///
+    /// This operation is *O*(*n*).
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, ToggleError> {
+        let snapshot: BinarySnapshot =
+            ciborium::from_reader(bytes).map_err(|e| ToggleError::Serialization(e.to_string()))?;
+        Self::from_binary_snapshot(snapshot)
+    }
+
+    /// Pack the values eight per byte along with the generation and the enum fingerprint.
+    fn binary_snapshot(&self) -> BinarySnapshot {
+        let mut bits = vec![0u8; self.toggles_value.len().div_ceil(8)];
+        for toggle_id in self.toggles_value.iter_ones() {
+            bits[toggle_id / 8] |= 1 << (toggle_id % 8);
+        }
+        BinarySnapshot {
+            fingerprint: Self::fingerprint(),
+            generation: self.generation,
+            bits,
+        }
+    }
+
+    /// Unpack a snapshot produced by `binary_snapshot`, rejecting the ones of a different enum.
+    fn from_binary_snapshot(snapshot: BinarySnapshot) -> Result<Self, ToggleError> {
+        if snapshot.fingerprint != Self::fingerprint() {
+            return Err(ToggleError::FingerprintMismatch);
+        }
+        let mut toggles = Self::new();
+        for toggle_id in 0..toggles.toggles_value.len() {
+            let byte = snapshot.bits.get(toggle_id / 8).copied().unwrap_or(0);
+            toggles.set(toggle_id, byte & (1 << (toggle_id % 8)) != 0);
+        }
+        toggles.generation = snapshot.generation;
+        Ok(toggles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum OtherToggles {
+        Toggle1,
+        Toggle3,
+    }
+
+    #[test]
+    #[cfg(feature = "postcard")]
+    fn test_round_trip() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        let bytes = toggles.to_bytes().unwrap();
+
+        let restored = EnumToggles::<TestToggles>::from_bytes(&bytes).unwrap();
+        assert!(!restored.get(TestToggles::Toggle1 as usize));
+        assert!(restored.get(TestToggles::Toggle2 as usize));
+        assert_eq!(restored.generation(), toggles.generation());
+
+        assert_eq!(
+            EnumToggles::<OtherToggles>::from_bytes(&bytes).err(),
+            Some(ToggleError::FingerprintMismatch)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cbor")]
+    fn test_cbor_round_trip() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        let bytes = toggles.to_cbor().unwrap();
+
+        let restored = EnumToggles::<TestToggles>::from_cbor(&bytes).unwrap();
+        assert!(restored.get(TestToggles::Toggle1 as usize));
+        assert!(!restored.get(TestToggles::Toggle2 as usize));
+        assert_eq!(
+            EnumToggles::<OtherToggles>::from_cbor(&bytes).err(),
+            Some(ToggleError::FingerprintMismatch)
+        );
+        assert!(matches!(
+            EnumToggles::<TestToggles>::from_cbor(&[0xff]),
+            Err(ToggleError::Serialization(_))
+        ));
+    }
+}
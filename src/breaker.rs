@@ -0,0 +1,131 @@
+use crate::{EnumToggles, Provenance};
+use std::time::{Duration, Instant};
+
+/// A toggle forced off by `EnumToggles::trip`.
+#[derive(Debug, Clone)]
+pub(crate) struct Trip {
+    until: Instant,
+    value: bool,
+    rollout: Option<u16>,
+    provenance: Provenance,
+}
+
+/// Circuit-breaker hooks, letting an error-rate monitor roll back a failing feature.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Force a toggle off for `cooldown`, recording `reason` as its provenance. File loads
+    /// during the cooldown keep it off, the loaded value being restored by `expire_trips`.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn trip(&mut self, toggle: T, reason: &str, cooldown: Duration) {
+        let toggle_id = self.toggle_id(&toggle);
+        if self.rejects_change(toggle_id, false) {
+            return;
+        }
+        let trip = match self.trips[toggle_id].take() {
+            Some(trip) => Trip {
+                until: Instant::now() + cooldown,
+                ..trip
+            },
+            None => Trip {
+                until: Instant::now() + cooldown,
+                value: self.get(toggle_id),
+                rollout: self.rollouts[toggle_id],
+                provenance: self.provenance[toggle_id].clone(),
+            },
+        };
+        self.assign(toggle_id, false, Provenance::Tripped(reason.to_string()));
+        self.trips[toggle_id] = Some(trip);
+    }
+
+    /// True if a toggle is forced off by `trip`, even once its cooldown elapsed until
+    /// `expire_trips` runs.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn is_tripped(&self, toggle: T) -> bool {
+        self.trips[self.toggle_id(&toggle)].is_some()
+    }
+
+    /// Restore the value, rollout and provenance of the toggles whose cooldown elapsed,
+    /// returning them.
+    /// Call it periodically, e.g. along with file reloads.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn expire_trips(&mut self) -> Vec<T> {
+        let now = Instant::now();
+        let mut restored = Vec::new();
+        for (toggle_id, toggle) in T::iter().enumerate() {
+            if self.trips[toggle_id]
+                .as_ref()
+                .is_some_and(|trip| trip.until <= now)
+            {
+                if let Some(trip) = self.trips[toggle_id].take() {
+                    if self.assign(toggle_id, trip.value, trip.provenance) {
+                        self.rollouts[toggle_id] = trip.rollout;
+                    }
+                    restored.push(toggle);
+                }
+            }
+        }
+        restored
+    }
+
+    /// Keep a tripped toggle off, remembering `value`, `rollout` and `provenance` for its
+    /// restoration. Returns false if the toggle is not tripped.
+    #[cfg(feature = "yaml")]
+    pub(crate) fn defer_tripped(
+        &mut self,
+        toggle_id: usize,
+        value: bool,
+        rollout: Option<u16>,
+        provenance: &Provenance,
+    ) -> bool {
+        match self.trips[toggle_id].as_mut() {
+            Some(trip) => {
+                trip.value = value;
+                trip.rollout = rollout;
+                trip.provenance = provenance.clone();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq, Debug)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_trip() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.trip(
+            TestToggles::Toggle1,
+            "error rate 12%",
+            Duration::from_secs(60),
+        );
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.is_tripped(TestToggles::Toggle1));
+        assert_eq!(
+            toggles.status()[0].provenance,
+            Provenance::Tripped("error rate 12%".to_string())
+        );
+        assert!(toggles.expire_trips().is_empty());
+
+        toggles.trip(TestToggles::Toggle1, "error rate 15%", Duration::ZERO);
+        assert_eq!(toggles.expire_trips(), vec![TestToggles::Toggle1]);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.is_tripped(TestToggles::Toggle1));
+        assert_eq!(toggles.status()[0].provenance, Provenance::Api);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{naming, EnumToggles, Provenance, ToggleError};
+use log::warn;
+use std::env;
+
+/// A source applied by `EnumTogglesBuilder::build`, in the order of declaration.
+enum Step<T> {
+    Set(T, bool),
+    #[cfg(feature = "yaml")]
+    File(String),
+    Env(String),
+}
+
+/// One-shot construction of `EnumToggles`, validating every source before returning.
+pub struct EnumTogglesBuilder<T> {
+    steps: Vec<Step<T>>,
+    strict: bool,
+}
+
+impl<T> Default for EnumTogglesBuilder<T> {
+    fn default() -> Self {
+        EnumTogglesBuilder {
+            steps: Vec::new(),
+            strict: false,
+        }
+    }
+}
+
+impl<T> EnumTogglesBuilder<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Create a new instance of `EnumTogglesBuilder` with all toggles disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable a toggle.
+    pub fn enable(mut self, toggle: T) -> Self {
+        self.steps.push(Step::Set(toggle, true));
+        self
+    }
+
+    /// Disable a toggle.
+    pub fn disable(mut self, toggle: T) -> Self {
+        self.steps.push(Step::Set(toggle, false));
+        self
+    }
+
+    /// Load the toggles of a yaml file.
+    #[cfg(feature = "yaml")]
+    pub fn from_file(mut self, filepath: &str) -> Self {
+        self.steps.push(Step::File(filepath.to_string()));
+        self
+    }
+
+    /// Read the toggles from environment variables, `FeatureA` being read from
+    /// `<prefix>FEATURE_A` with the value `1`, `0`, `true`, `false`, `yes`, `no`, `on` or `off`
+    /// in any case, as written by `to_env`. The variables read count as one load.
+    pub fn from_env(mut self, prefix: &str) -> Self {
+        self.steps.push(Step::Env(prefix.to_string()));
+        self
+    }
+
+    /// Fail on unknown keys in files and invalid values in environment variables instead of
+    /// logging a warning.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Apply the sources in the order they were declared, later ones overriding earlier ones.
+    pub fn build(self) -> Result<EnumToggles<T>, ToggleError> {
+        let mut toggles = EnumToggles::new();
+        for step in self.steps {
+            match step {
+                Step::Set(toggle, value) => toggles.set_variant(toggle, value),
+                #[cfg(feature = "yaml")]
+                Step::File(path) if self.strict => {
+                    toggles.load_from_file_strict(&path)?;
+                }
+                #[cfg(feature = "yaml")]
+                Step::File(path) => {
+                    toggles.load_from_file(&path)?;
+                }
+                Step::Env(prefix) => {
+                    let mut read = false;
+                    for (toggle_id, toggle) in T::iter().enumerate() {
+                        let var = format!(
+                            "{}{}",
+                            prefix,
+                            naming::to_screaming_snake_case(toggle.as_ref())
+                        );
+                        let Ok(raw) = env::var(&var) else {
+                            continue;
+                        };
+                        match naming::str_to_bool(&raw) {
+                            Some(value) => {
+                                toggles.assign(toggle_id, value, Provenance::Env(var.clone()));
+                                read = true;
+                            }
+                            None if self.strict => {
+                                return Err(ToggleError::Load(format!(
+                                    "Invalid value {} for {}",
+                                    raw, var
+                                )))
+                            }
+                            None => warn!("Invalid value {} for {}", raw, var),
+                        }
+                    }
+                    if read {
+                        toggles.record_load(Provenance::Env(format!("{}*", prefix)));
+                    }
+                }
+            }
+        }
+        Ok(toggles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "yaml")]
+    use std::io::Write;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_build_from_env() {
+        env::set_var("BUILDER_TEST_TOGGLE1", "True");
+        let toggles = EnumTogglesBuilder::<TestToggles>::new()
+            .enable(TestToggles::Toggle2)
+            .from_env("BUILDER_TEST_")
+            .build()
+            .unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(
+            toggles.status()[0].provenance,
+            Provenance::Env("BUILDER_TEST_TOGGLE1".to_string())
+        );
+        assert_eq!(toggles.generation(), 1);
+
+        env::set_var("BUILDER_STRICT_TOGGLE2", "maybe");
+        let builder = EnumTogglesBuilder::<TestToggles>::new().from_env("BUILDER_STRICT_");
+        assert!(builder.build().is_ok());
+        let builder = EnumTogglesBuilder::<TestToggles>::new()
+            .from_env("BUILDER_STRICT_")
+            .strict(true);
+        assert!(matches!(builder.build(), Err(ToggleError::Load(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_build_from_file() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "VAR1: 0").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let toggles = EnumTogglesBuilder::<TestToggles>::new()
+            .from_file(filepath)
+            .disable(TestToggles::Toggle1)
+            .build()
+            .unwrap();
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+
+        let result = EnumTogglesBuilder::<TestToggles>::new()
+            .from_file(filepath)
+            .strict(true)
+            .build();
+        assert_eq!(
+            result.err(),
+            Some(ToggleError::UnknownKeys(vec!["VAR1".to_string()]))
+        );
+        assert!(matches!(
+            EnumTogglesBuilder::<TestToggles>::new()
+                .from_file("/nonexistent/toggles.yaml")
+                .build(),
+            Err(ToggleError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{json, EnumToggles, Provenance};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of file loads kept for `export_support_bundle`.
+const LOAD_HISTORY_SIZE: usize = 16;
+
+/// A file load recorded for `export_support_bundle`.
+#[derive(Debug, Clone)]
+pub(crate) struct LoadRecord {
+    pub(crate) generation: u64,
+    pub(crate) provenance: Provenance,
+    pub(crate) loaded_at: SystemTime,
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn provenance_json(provenance: &Provenance) -> String {
+    let (kind, source) = match provenance {
+        Provenance::Default => ("default", None),
+        Provenance::Api => ("api", None),
+        Provenance::File(path) => ("file", Some(path)),
+        Provenance::Env(var) => ("env", Some(var)),
+        Provenance::Reverted => ("reverted", None),
+        Provenance::Tripped(reason) => ("tripped", Some(reason)),
+        Provenance::Scheduled => ("scheduled", None),
+    };
+    match source {
+        Some(source) => format!(
+            "{{\"kind\":\"{}\",\"source\":{}}}",
+            kind,
+            json::quote(source)
+        ),
+        None => format!("{{\"kind\":\"{}\"}}", kind),
+    }
+}
+
+fn optional_json(value: &Option<String>) -> String {
+    value.as_deref().map_or("null".to_string(), json::quote)
+}
+
+/// Diagnostics for support tickets.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Count a load in the generation and the load history, dropping its oldest record.
+    pub(crate) fn record_load(&mut self, provenance: Provenance) {
+        self.generation += 1;
+        if self.load_history.len() == LOAD_HISTORY_SIZE {
+            self.load_history.pop_front();
+        }
+        self.load_history.push_back(LoadRecord {
+            generation: self.generation,
+            provenance,
+            loaded_at: SystemTime::now(),
+        });
+    }
+
+    /// Export the value, source and last change of every toggle as a JSON array, to serve
+    /// from a status endpoint or a dashboard. `last_changed` is in unix seconds, `null` if
+    /// the toggle never changed.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn export_json(&self) -> String {
+        let toggles: Vec<String> = T::iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| {
+                format!(
+                    "{{\"name\":{},\"value\":{},\"source\":{},\"last_changed\":{}}}",
+                    json::quote(toggle.as_ref()),
+                    self.get(toggle_id),
+                    provenance_json(&self.provenance[toggle_id]),
+                    self.changed_at[toggle_id]
+                        .map_or("null".to_string(), |t| unix_secs(t).to_string()),
+                )
+            })
+            .collect();
+        format!("[{}]", toggles.join(","))
+    }
+
+    /// Export the full state as a single JSON document to attach to a support ticket: the
+    /// value, default, provenance, last change and metadata of every toggle, the generation,
+    /// the fingerprint, the state hash and the last file loads.
+    ///
+    /// Times are unix seconds, hashes hex strings.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn export_support_bundle(&self) -> String {
+        let toggles: Vec<String> = T::iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| {
+                let metadata = &self.metadata[toggle_id];
+                format!(
+                    "{{\"name\":{},\"value\":{},\"default\":{},\"provenance\":{},\"last_changed\":{},\"startup_only\":{},\"tripped\":{},\"description\":{},\"owner\":{},\"ticket\":{},\"expires\":{}}}",
+                    json::quote(toggle.as_ref()),
+                    self.get(toggle_id),
+                    self.defaults[toggle_id],
+                    provenance_json(&self.provenance[toggle_id]),
+                    self.changed_at[toggle_id]
+                        .map_or("null".to_string(), |t| unix_secs(t).to_string()),
+                    self.startup_only[toggle_id],
+                    self.trips[toggle_id].is_some(),
+                    optional_json(&metadata.description),
+                    optional_json(&metadata.owner),
+                    optional_json(&metadata.ticket),
+                    optional_json(&metadata.expires),
+                )
+            })
+            .collect();
+        let loads: Vec<String> = self
+            .load_history
+            .iter()
+            .map(|load| {
+                format!(
+                    "{{\"generation\":{},\"provenance\":{},\"loaded_at\":{}}}",
+                    load.generation,
+                    provenance_json(&load.provenance),
+                    unix_secs(load.loaded_at)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"version\":1,\"generated_at\":{},\"generation\":{},\"fingerprint\":\"{:016x}\",\"hash\":\"{:016x}\",\"toggles\":[{}],\"loads\":[{}]}}",
+            unix_secs(SystemTime::now()),
+            self.generation,
+            Self::fingerprint(),
+            self.state_hash(),
+            toggles.join(","),
+            loads.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EnumToggles, ToggleMetadata};
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_export_support_bundle() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.describe(
+            TestToggles::Toggle2,
+            ToggleMetadata {
+                owner: Some("search\"team".to_string()),
+                ..Default::default()
+            },
+        );
+        let bundle = toggles.export_support_bundle();
+        assert!(bundle.starts_with("{\"version\":1,\"generated_at\":"));
+        assert!(bundle.contains("\"generation\":0,"));
+        assert!(bundle.contains(&format!("\"hash\":\"{:016x}\"", toggles.state_hash())));
+        assert!(bundle.contains("{\"name\":\"Toggle1\",\"value\":true,\"default\":false,\"provenance\":{\"kind\":\"api\"},\"last_changed\":"));
+        assert!(bundle.contains("{\"name\":\"Toggle2\",\"value\":false,\"default\":false,\"provenance\":{\"kind\":\"default\"},\"last_changed\":null,\"startup_only\":false,\"tripped\":false,\"description\":null,\"owner\":\"search\\\"team\",\"ticket\":null,\"expires\":null}"));
+        assert!(bundle.ends_with("\"loads\":[]}"));
+    }
+
+    #[test]
+    fn test_export_json() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        let json = toggles.export_json();
+        assert!(json.starts_with(
+            "[{\"name\":\"Toggle1\",\"value\":true,\"source\":{\"kind\":\"api\"},\"last_changed\":"
+        ));
+        assert!(json.ends_with(
+            ",{\"name\":\"Toggle2\",\"value\":false,\"source\":{\"kind\":\"default\"},\"last_changed\":null}]"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_export_support_bundle_loads() {
+        std::env::set_var("ENUM_TOGGLES_TEST_BUNDLE", "{\"Toggle1\": 1}");
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        for _ in 0..super::LOAD_HISTORY_SIZE + 2 {
+            toggles
+                .load_from_env_blob("ENUM_TOGGLES_TEST_BUNDLE")
+                .unwrap();
+        }
+        let bundle = toggles.export_support_bundle();
+        assert_eq!(
+            bundle.matches("\"loaded_at\"").count(),
+            super::LOAD_HISTORY_SIZE
+        );
+        assert!(bundle.contains("\"loads\":[{\"generation\":3,\"provenance\":{\"kind\":\"env\",\"source\":\"ENUM_TOGGLES_TEST_BUNDLE\"},\"loaded_at\":"));
+    }
+}
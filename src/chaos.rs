@@ -0,0 +1,113 @@
+use crate::ReadToggles;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::thread;
+use std::time::Duration;
+
+/// Fault injection driven by toggles, so chaos experiments are controlled from the flag file.
+///
+/// A fault fires when its toggle is enabled and the dice roll under the probability.
+pub struct Chaos<'a, R> {
+    toggles: &'a R,
+    probability: f64,
+}
+
+impl<'a, R> Chaos<'a, R> {
+    /// Create a new instance of `Chaos` firing every time its toggle is enabled.
+    pub fn new(toggles: &'a R) -> Self {
+        Chaos {
+            toggles,
+            probability: 1.0,
+        }
+    }
+
+    /// Fire with the given probability, clamped to `[0, 1]`.
+    pub fn with_probability(mut self, probability: f64) -> Self {
+        self.probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Return the result of `fault` when the fault fires, `Ok(())` otherwise.
+    pub fn maybe_fail<T, E>(
+        &self,
+        toggle: T,
+        fault: impl FnOnce() -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        R: ReadToggles<T>,
+    {
+        if self.fires(toggle) {
+            fault()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sleep for `latency` when the fault fires, returns whether it did.
+    pub fn maybe_delay<T>(&self, toggle: T, latency: Duration) -> bool
+    where
+        R: ReadToggles<T>,
+    {
+        let fires = self.fires(toggle);
+        if fires {
+            thread::sleep(latency);
+        }
+        fires
+    }
+
+    fn fires<T>(&self, toggle: T) -> bool
+    where
+        R: ReadToggles<T>,
+    {
+        if !self.toggles.is_enabled(toggle) {
+            return false;
+        }
+        if self.probability >= 1.0 {
+            return true;
+        }
+        // RandomState is seeded per instance, good enough for chaos and avoids a rand dependency.
+        let roll = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        roll < self.probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockToggles;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        ChaosDbTimeout,
+        ChaosSlowNetwork,
+    }
+
+    #[test]
+    fn test_maybe_fail() {
+        let toggles = MockToggles::new().with(TestToggles::ChaosDbTimeout, true);
+        let chaos = Chaos::new(&toggles);
+        assert_eq!(
+            chaos.maybe_fail(TestToggles::ChaosDbTimeout, || Err("timeout")),
+            Err("timeout")
+        );
+        assert_eq!(
+            chaos.maybe_fail(TestToggles::ChaosSlowNetwork, || Err("timeout")),
+            Ok(())
+        );
+
+        let chaos = Chaos::new(&toggles).with_probability(0.0);
+        assert_eq!(
+            chaos.maybe_fail(TestToggles::ChaosDbTimeout, || Err("timeout")),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_maybe_delay() {
+        let toggles = MockToggles::new().with(TestToggles::ChaosSlowNetwork, true);
+        let chaos = Chaos::new(&toggles);
+        assert!(chaos.maybe_delay(TestToggles::ChaosSlowNetwork, Duration::from_millis(1)));
+        assert!(!chaos.maybe_delay(TestToggles::ChaosDbTimeout, Duration::from_millis(1)));
+    }
+}
@@ -0,0 +1,79 @@
+/// Naming rules that toggle names and file keys are checked against when loading.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamingConvention {
+    pascal_case: bool,
+    max_length: Option<usize>,
+    prefix: Option<String>,
+}
+
+impl NamingConvention {
+    /// Create a new instance of `NamingConvention` accepting every name.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require `PascalCase` names: an uppercase first letter, at least one lowercase letter
+    /// and only ascii alphanumerics.
+    pub fn pascal_case(mut self) -> Self {
+        self.pascal_case = true;
+        self
+    }
+
+    /// Require names of at most `max_length` characters.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Require names to start with `prefix`.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// List the rules broken by a name.
+    pub fn check(&self, name: &str) -> Vec<String> {
+        let mut violations = Vec::new();
+        if self.pascal_case
+            && !(name.starts_with(|c: char| c.is_ascii_uppercase())
+                && name.chars().any(|c| c.is_ascii_lowercase())
+                && name.chars().all(|c| c.is_ascii_alphanumeric()))
+        {
+            violations.push(format!("{} is not PascalCase", name));
+        }
+        if let Some(max_length) = self.max_length {
+            if name.chars().count() > max_length {
+                violations.push(format!("{} is longer than {} characters", name, max_length));
+            }
+        }
+        if let Some(prefix) = &self.prefix {
+            if !name.starts_with(prefix.as_str()) {
+                violations.push(format!("{} does not start with {}", name, prefix));
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let convention = NamingConvention::new()
+            .pascal_case()
+            .max_length(12)
+            .prefix("Ff");
+        assert!(convention.check("FfNewSearch").is_empty());
+        assert_eq!(
+            convention.check("new_search_page"),
+            vec![
+                "new_search_page is not PascalCase",
+                "new_search_page is longer than 12 characters",
+                "new_search_page does not start with Ff",
+            ]
+        );
+        assert!(NamingConvention::new().check("any name").is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{yaml, ToggleError};
+
+/// Parse the toggles defined in a `.env` file, in file order. Only the `KEY=VALUE` lines
+/// whose key starts with `prefix` are read, the prefix being stripped, so the file can hold
+/// the rest of the application configuration as well.
+pub(crate) fn parse(content: &str, prefix: &str) -> Result<Vec<(String, bool)>, ToggleError> {
+    yaml::text_entries(parse_raw(content, prefix)?, prefix)
+}
+
+/// Parse the `KEY=VALUE` lines of a `.env` file like `parse`, keeping the values as text.
+pub(crate) fn parse_raw(content: &str, prefix: &str) -> Result<Vec<(String, String)>, ToggleError> {
+    let mut entries = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, raw) = line
+            .split_once('=')
+            .ok_or_else(|| ToggleError::TypeMismatch {
+                key: format!("line {}", number + 1),
+                expected: "KEY=VALUE".to_string(),
+            })?;
+        let Some(key) = key.trim().strip_prefix(prefix) else {
+            continue;
+        };
+        entries.push((key.to_string(), unquote(raw).to_string()));
+    }
+    Ok(entries)
+}
+
+/// Strip the quotes around a value, or the trailing ` # comment` of an unquoted one.
+fn unquote(raw: &str) -> &str {
+    let raw = raw.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = raw.strip_prefix(quote).and_then(|r| r.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    raw.split(" #").next().unwrap_or(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "# toggles\nDATABASE_URL=postgres://db\nAPP_FEATURE_A=1\n\
+            export APP_FEATURE_B=\"off\"\nAPP_FEATURE_C=yes # enabled for QA\n";
+        assert_eq!(
+            parse(content, "APP_").unwrap(),
+            vec![
+                ("FEATURE_A".to_string(), true),
+                ("FEATURE_B".to_string(), false),
+                ("FEATURE_C".to_string(), true)
+            ]
+        );
+        assert!(matches!(
+            parse(content, ""),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            parse("APP_FEATURE_A\n", "APP_"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+use thiserror::Error;
+
+/// Errors raised while building or loading toggles.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ToggleError {
+    /// Keys that don't match any toggle of the enum.
+    #[error("Unknown toggles: {}", .0.join(", "))]
+    UnknownKeys(Vec<String>),
+    /// Keys declared more than once in a file.
+    #[error("Duplicate toggles: {}", .0.join(", "))]
+    DuplicateKeys(Vec<String>),
+    /// Descriptions of the violated invariants.
+    #[error("Invariants violated: {}", .0.join(", "))]
+    InvariantViolations(Vec<String>),
+    /// A snapshot could not be encoded or decoded.
+    #[error("Serialization failed: {0}")]
+    Serialization(String),
+    /// A snapshot was produced for a different enum.
+    #[error("The snapshot was produced for a different enum")]
+    FingerprintMismatch,
+    /// A source could not be read or holds an invalid value.
+    #[error("Loading failed: {0}")]
+    Load(String),
+    /// A toggle id beyond the number of toggles.
+    #[error(
+        "Out-of-bounds access. The provided toggle_id is {toggle_id}, but the array size is {len}."
+    )]
+    OutOfBounds {
+        /// The provided toggle id.
+        toggle_id: usize,
+        /// The number of toggles.
+        len: usize,
+    },
+    /// A startup-only toggle was changed after startup.
+    #[error("Rejected change of the startup-only toggle {0} after startup")]
+    StartupOnly(String),
+    /// A file could not be read.
+    #[error("I/O error: {0}")]
+    Io(String),
+    /// A document is over the parse limits.
+    #[error("Parse limit exceeded: {0}")]
+    LimitExceeded(String),
+    /// A document is not valid yaml.
+    #[error("Invalid yaml: {0}")]
+    Yaml(String),
+    /// A document is not valid TOML.
+    #[error("Invalid TOML: {0}")]
+    Toml(String),
+    /// A document is not valid RON.
+    #[error("Invalid RON: {0}")]
+    Ron(String),
+    /// A file doesn't declare every toggle exactly once.
+    #[error(
+        "Incomplete file: missing {}, duplicated {}, unknown {}",
+        .missing_variants.join(", "),
+        .duplicate_keys.join(", "),
+        .unknown_keys.join(", ")
+    )]
+    Incomplete {
+        /// Toggles not declared in the file.
+        missing_variants: Vec<String>,
+        /// Keys declared more than once.
+        duplicate_keys: Vec<String>,
+        /// Keys that don't match any toggle.
+        unknown_keys: Vec<String>,
+    },
+    /// A key or value of a document has the wrong type.
+    #[error("Invalid {key}: expected {expected}")]
+    TypeMismatch {
+        /// The key, or the key whose value is invalid.
+        key: String,
+        /// The expected type.
+        expected: String,
+    },
+}
+
+impl From<std::io::Error> for ToggleError {
+    fn from(error: std::io::Error) -> Self {
+        ToggleError::Io(error.to_string())
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<yaml_rust::ScanError> for ToggleError {
+    fn from(error: yaml_rust::ScanError) -> Self {
+        ToggleError::Yaml(error.to_string())
+    }
+}
@@ -0,0 +1,29 @@
+use std::time::SystemTime;
+
+/// A toggle evaluated for a context key, to be joined with downstream metrics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exposure {
+    /// Name of the toggle.
+    pub toggle: String,
+    /// Stable hash of the context key, the key itself is never exposed.
+    pub key_hash: u64,
+    /// Value returned to the caller.
+    pub decision: bool,
+    /// Time of the evaluation.
+    pub timestamp: SystemTime,
+}
+
+/// Receive the exposure events emitted by `EnumToggles::get_for` and `EnumToggles::rollout`.
+pub trait ExposureSink: Send + Sync {
+    /// Called once per evaluation, keep it cheap.
+    fn record(&self, exposure: &Exposure);
+}
+
+impl<F> ExposureSink for F
+where
+    F: Fn(&Exposure) + Send + Sync,
+{
+    fn record(&self, exposure: &Exposure) {
+        self(exposure)
+    }
+}
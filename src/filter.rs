@@ -0,0 +1,90 @@
+use crate::EnumToggles;
+use tracing_subscriber::filter::{EnvFilter, ParseError};
+use tracing_subscriber::reload;
+
+/// Log verbosity driven by toggles, e.g. `VerboseSqlLogging` enabling `sql=debug`.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Join the `base` directives with the directives of the enabled toggles of `rules`,
+    /// e.g. `info,sql=debug`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn log_directives(&self, base: &str, rules: &[(T, &str)]) -> String {
+        let mut directives: Vec<&str> = Vec::new();
+        if !base.is_empty() {
+            directives.push(base);
+        }
+        for (toggle, directive) in rules {
+            if self.is_enabled_ref(toggle) {
+                directives.push(directive);
+            }
+        }
+        directives.join(",")
+    }
+
+    /// Build an `EnvFilter` from `log_directives`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn env_filter(&self, base: &str, rules: &[(T, &str)]) -> Result<EnvFilter, ParseError> {
+        EnvFilter::try_new(self.log_directives(base, rules))
+    }
+
+    /// Replace the filter behind a reload handle by `env_filter`, call it after each change
+    /// of the toggles.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn update_log_filter<S>(
+        &self,
+        handle: &reload::Handle<EnvFilter, S>,
+        base: &str,
+        rules: &[(T, &str)],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        handle.reload(self.env_filter(base, rules)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::EnumToggles;
+    use strum_macros::{AsRefStr, EnumIter};
+    use tracing_subscriber::filter::EnvFilter;
+    use tracing_subscriber::prelude::*;
+    use tracing_subscriber::{reload, Registry};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        VerboseSqlLogging,
+        VerboseHttpLogging,
+    }
+
+    const RULES: [(TestToggles, &str); 2] = [
+        (TestToggles::VerboseSqlLogging, "sql=debug"),
+        (TestToggles::VerboseHttpLogging, "http=trace"),
+    ];
+
+    #[test]
+    fn test_log_directives() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert_eq!(toggles.log_directives("info", &RULES), "info");
+        toggles.set(TestToggles::VerboseSqlLogging as usize, true);
+        assert_eq!(toggles.log_directives("info", &RULES), "info,sql=debug");
+        assert_eq!(toggles.log_directives("", &RULES), "sql=debug");
+        assert!(toggles.env_filter("info", &RULES).is_ok());
+        assert!(toggles.env_filter("info=loud", &RULES).is_err());
+    }
+
+    #[test]
+    fn test_update_log_filter() {
+        let (layer, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let _subscriber = Registry::default().with(layer);
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::VerboseHttpLogging as usize, true);
+        toggles.update_log_filter(&handle, "warn", &RULES).unwrap();
+        let current = handle.with_current(|filter| filter.to_string()).unwrap();
+        assert!(current.contains("http=trace"));
+        assert!(!current.contains("sql=debug"));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::EnumToggles;
+use std::fmt;
+
+/// Text layouts of `EnumToggles::format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// One `FeatureA=true` line per toggle, used by `Display`.
+    #[default]
+    Lines,
+    /// Aligned `Toggle` and `Value` columns.
+    Table,
+    /// One `0` or `1` character per toggle, in declaration order.
+    Bits,
+}
+
+/// Human readable output of the toggle states.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Print the toggle states in the given layout.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn format(&self, format: Format) -> String {
+        match format {
+            Format::Lines => T::iter()
+                .enumerate()
+                .map(|(toggle_id, toggle)| format!("{}={}\n", toggle.as_ref(), self.get(toggle_id)))
+                .collect(),
+            Format::Table => {
+                let width = T::iter()
+                    .map(|toggle| toggle.as_ref().len())
+                    .chain(std::iter::once("Toggle".len()))
+                    .max()
+                    .unwrap_or(0);
+                let mut table = format!("{:width$}  Value\n", "Toggle", width = width);
+                for (toggle_id, toggle) in T::iter().enumerate() {
+                    table.push_str(&format!(
+                        "{:width$}  {}\n",
+                        toggle.as_ref(),
+                        self.get(toggle_id),
+                        width = width
+                    ));
+                }
+                table
+            }
+            Format::Bits => (0..self.toggles_value.len())
+                .map(|toggle_id| if self.get(toggle_id) { '1' } else { '0' })
+                .collect(),
+        }
+    }
+}
+
+/// Display the toggle states as `FeatureA=true` lines.
+impl<T> fmt::Display for EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(Format::Lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        LongerToggle2,
+    }
+
+    #[test]
+    fn test_format() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::LongerToggle2 as usize, true);
+        assert_eq!(
+            toggles.format(Format::Lines),
+            "Toggle1=false\nLongerToggle2=true\n"
+        );
+        assert_eq!(toggles.to_string(), toggles.format(Format::Lines));
+        assert_eq!(
+            toggles.format(Format::Table),
+            "Toggle         Value\nToggle1        false\nLongerToggle2  true\n"
+        );
+        assert_eq!(toggles.format(Format::Bits), "01");
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{EnumToggles, ReadToggles};
+use bitvec::prelude::*;
+use std::marker::PhantomData;
+
+/// Read-only toggles returned by `EnumToggles::freeze`, `Send + Sync` whatever the enum.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FrozenToggles<T> {
+    toggles_value: BitVec,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> FrozenToggles<T> {
+    /// Get the bool value of a toggle by toggle id.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn get(&self, toggle_id: usize) -> bool {
+        self.toggles_value[toggle_id]
+    }
+}
+
+impl<T> ReadToggles<T> for FrozenToggles<T>
+where
+    T: strum::IntoEnumIterator + PartialEq + 'static,
+{
+    fn is_enabled(&self, toggle: T) -> bool {
+        T::iter()
+            .position(|t| t == toggle)
+            .is_some_and(|toggle_id| self.get(toggle_id))
+    }
+}
+
+/// Read-only after init.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Consume the toggles into a read-only `FrozenToggles`, dropping the loaders, history and
+    /// exposure sink.
+    pub fn freeze(self) -> FrozenToggles<T> {
+        FrozenToggles {
+            toggles_value: self.toggles_value,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    fn assert_send_sync<S: Send + Sync>(_: &S) {}
+
+    #[test]
+    fn test_freeze() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        let frozen = toggles.freeze();
+        assert_send_sync(&frozen);
+        assert!(!frozen.get(TestToggles::Toggle1 as usize));
+        assert!(frozen.is_enabled(TestToggles::Toggle2));
+    }
+}
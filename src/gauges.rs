@@ -0,0 +1,65 @@
+use crate::EnumToggles;
+
+/// Name of the gauge holding the current value of each toggle, labelled by `toggle`.
+pub const TOGGLE_GAUGE: &str = "enum_toggles_enabled";
+
+pub(crate) fn publish(name: &str, value: bool) {
+    metrics::gauge!(TOGGLE_GAUGE, "toggle" => name.to_string()).set(value as u8 as f64);
+}
+
+/// Current-state gauges, separate from any evaluation counter.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Set the 0/1 gauge of every toggle. Gauges are then kept up to date on change.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn publish_gauges(&self) {
+        for (toggle_id, toggle) in T::iter().enumerate() {
+            publish(toggle.as_ref(), self.get(toggle_id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_gauges() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+            toggles.publish_gauges();
+            toggles.set(TestToggles::Toggle2 as usize, true);
+        });
+
+        let mut gauges: Vec<(String, f64)> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter_map(|(key, _, _, value)| match value {
+                DebugValue::Gauge(value) => Some((
+                    key.key().labels().next().unwrap().value().to_string(),
+                    value.into_inner(),
+                )),
+                _ => None,
+            })
+            .collect();
+        gauges.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            gauges,
+            vec![("Toggle1".to_string(), 0.0), ("Toggle2".to_string(), 1.0)]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{yaml, ToggleError};
+
+/// Parse the toggles defined in an INI file, in file order. The keys of a `[Section]` are
+/// prefixed with the section name, so `NewFlow = 1` under `[Checkout]` declares
+/// `CheckoutNewFlow`, the keys before the first section are read as is.
+pub(crate) fn parse(content: &str) -> Result<Vec<(String, bool)>, ToggleError> {
+    yaml::text_entries(parse_raw(content)?, "")
+}
+
+/// Parse the keys of an INI file like `parse`, keeping the values as text.
+pub(crate) fn parse_raw(content: &str) -> Result<Vec<(String, String)>, ToggleError> {
+    let mut entries = Vec::new();
+    let mut section = "";
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with([';', '#']) {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim();
+            continue;
+        }
+        let (key, raw) = line
+            .split_once('=')
+            .ok_or_else(|| ToggleError::TypeMismatch {
+                key: format!("line {}", number + 1),
+                expected: "a [section] or key = value".to_string(),
+            })?;
+        let key = format!("{}{}", section, key.trim());
+        entries.push((key, raw.trim().to_string()));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content =
+            "; legacy flags\nFeatureA = 1\n\n[Checkout]\nNewFlow = on\n# old\nExpress=0\n";
+        assert_eq!(
+            parse(content).unwrap(),
+            vec![
+                ("FeatureA".to_string(), true),
+                ("CheckoutNewFlow".to_string(), true),
+                ("CheckoutExpress".to_string(), false)
+            ]
+        );
+        assert!(matches!(
+            parse("[Checkout]\nNewFlow\n"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            parse("FeatureA = maybe\n"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+    }
+}
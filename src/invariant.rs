@@ -0,0 +1,11 @@
+/// A rule on a combination of toggles checked by `EnumToggles::assert_invariants`.
+pub enum Invariant<T> {
+    /// The two toggles can't be enabled together.
+    Exclusive(T, T),
+    /// The first toggle can only be enabled along with the second.
+    Requires(T, T),
+    /// The toggle must be enabled.
+    Enabled(T),
+    /// The toggle must be declared in the last loaded file, e.g. a mandatory kill switch.
+    Declared(T),
+}
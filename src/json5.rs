@@ -0,0 +1,60 @@
+/// Turn a JSON5 document into one the yaml parser reads: comments are dropped and trailing
+/// commas removed, unquoted keys and single-quoted strings being valid yaml already.
+pub(crate) fn strip(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            result.push(c);
+            if c == '\\' {
+                result.extend(chars.next());
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"' | '\'', _) => {
+                quote = Some(c);
+                result.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&n| n != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for n in chars.by_ref() {
+                    if previous == '*' && n == '/' {
+                        break;
+                    }
+                    previous = n;
+                }
+                result.push(' ');
+            }
+            ('}' | ']', _) => {
+                let kept = result.trim_end().len();
+                if result[..kept].ends_with(',') {
+                    result.truncate(kept - 1);
+                }
+                result.push(c);
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip() {
+        let content = "{\n  // Rolled out to everyone\n  FeatureA: true, /* until Q3 */\n  \
+            'FeatureB': 0,\n  \"Url\": \"http://x//y\",\n}\n";
+        assert_eq!(
+            strip(content),
+            "{\n  \n  FeatureA: true,  \n  'FeatureB': 0,\n  \"Url\": \"http://x//y\"}\n"
+        );
+    }
+}
@@ -2,6 +2,10 @@
 //! Toggle states are read-only and accessed in O(1) time.
 //! There's a direct relationship where each string name corresponds to a unique name in the enum.
 //!
+//! The core only holds the bit store and the enum mapping, file formats and integrations are
+//! behind cargo features: `yaml` (default), `cbor`, `metrics`, `postcard`, `ron`, `systemd`,
+//! `tokio`, `toml` and `tracing`.
+//!
 //! # Example
 //!
 //! - File `toggles.yaml` conains:
@@ -49,6 +53,7 @@
 //!     match filepath {
 //!         Ok(path) => {
 //!             if !path.is_empty() {
+//!                 #[cfg(feature = "yaml")]
 //!                 toggle.load_from_file(&path);
 //!             }
 //!         }
@@ -60,15 +65,179 @@
 //! println!("{:?}", TOGGLES.deref());
 //! ```
 //!
+//! - Typed settings next to the toggles
+//! ```rust
+//! use enum_toggles::EnumSettings;
+//! use std::time::Duration;
+//! use strum_macros::{AsRefStr, EnumIter};
+//!
+//! #[derive(AsRefStr, EnumIter, PartialEq)]
+//! enum MySetting {
+//!     RequestTimeout,
+//!     IdleTimeout,
+//! }
+//!
+//! let mut settings: EnumSettings<MySetting, Duration> = EnumSettings::new(Duration::from_secs(30));
+//! settings.set(MySetting::IdleTimeout as usize, Duration::from_secs(300));
+//! // settings.load_from_file("settings.yaml"); // `RequestTimeout: 5s`
+//! println!("{:?}", settings);
+//! ```
+//!
+
+#[cfg(any(feature = "postcard", feature = "cbor"))]
+mod binary;
+mod breaker;
+mod builder;
+mod bundle;
+mod chaos;
+mod convention;
+#[cfg(feature = "yaml")]
+mod dotenv;
+mod error;
+mod exposure;
+#[cfg(feature = "tracing")]
+mod filter;
+mod format;
+mod frozen;
+#[cfg(feature = "metrics")]
+mod gauges;
+mod hash;
+#[cfg(feature = "yaml")]
+mod ini;
+mod invariant;
+mod json;
+#[cfg(feature = "yaml")]
+mod json5;
+#[cfg(feature = "yaml")]
+mod limiter;
+#[cfg(feature = "yaml")]
+mod limits;
+#[cfg(feature = "yaml")]
+mod load;
+mod mock;
+mod naming;
+mod ops;
+#[cfg(feature = "yaml")]
+mod profile;
+#[cfg(feature = "yaml")]
+mod properties;
+mod report;
+mod rollout;
+#[cfg(feature = "ron")]
+mod ron;
+mod save;
+mod schedule;
+mod schema;
+#[cfg(feature = "tokio")]
+mod scoped;
+mod settings;
+mod snapshot;
+#[cfg(feature = "yaml")]
+mod source;
+mod startup;
+mod status;
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+mod systemd;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use builder::EnumTogglesBuilder;
+pub use chaos::Chaos;
+pub use convention::NamingConvention;
+pub use error::ToggleError;
+pub use exposure::{Exposure, ExposureSink};
+pub use format::Format;
+pub use frozen::FrozenToggles;
+#[cfg(feature = "metrics")]
+pub use gauges::TOGGLE_GAUGE;
+pub use invariant::Invariant;
+#[cfg(feature = "yaml")]
+pub use limits::ParseLimits;
+pub use mock::MockToggles;
+pub use report::LoadReport;
+#[cfg(feature = "tokio")]
+pub use scoped::TaskOverrides;
+pub use settings::EnumSettings;
+#[cfg(feature = "yaml")]
+pub use settings::SettingValue;
+pub use snapshot::ToggleSnapshot;
+#[cfg(feature = "yaml")]
+pub use source::SourceFormat;
+pub use status::{Provenance, ToggleMetadata, ToggleStatus};
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+pub use systemd::{sd_notify, ReloadSignal};
 
 use bitvec::prelude::*;
-use std::fs;
-use std::{collections::HashMap, fmt};
-use yaml_rust::{Yaml, YamlLoader};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::io;
+use std::mem::{self, Discriminant};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Unsigned integer usable as a bitflags-style mask, bit `i` holding the toggle id `i`.
+pub trait BitMask: Copy {
+    /// Number of bits of the mask.
+    const BITS: u32;
+    /// Widen the mask.
+    fn to_u128(self) -> u128;
+    /// Narrow a mask, the caller guarantees it fits.
+    fn from_u128(value: u128) -> Self;
+}
+
+macro_rules! impl_bit_mask {
+    ($($t:ty),*) => {
+        $(
+            impl BitMask for $t {
+                const BITS: u32 = <$t>::BITS;
+                fn to_u128(self) -> u128 {
+                    self as u128
+                }
+                fn from_u128(value: u128) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_mask!(u8, u16, u32, u64, u128);
+
+/// Read access to the toggle values, implemented by [`EnumToggles`] and [`MockToggles`] so
+/// code can be written against the trait and tested without files or globals.
+pub trait ReadToggles<T> {
+    /// Get the bool value of a toggle.
+    fn is_enabled(&self, toggle: T) -> bool;
+}
+
+/// A toggle whose value differs between two states, as `(toggle, before, after)`.
+pub type ToggleChange<T> = (T, bool, bool);
 
 /// Contains the toggle value for each item of the enum T.
 pub struct EnumToggles<T> {
     toggles_value: BitVec,
+    defaults: BitVec,
+    declared: BitVec,
+    previous: Option<BitVec>,
+    previous_rollouts: Vec<Option<u16>>,
+    provenance: Vec<Provenance>,
+    changed_at: Vec<Option<SystemTime>>,
+    metadata: Vec<ToggleMetadata>,
+    trips: Vec<Option<breaker::Trip>>,
+    rollouts: Vec<Option<u16>>,
+    startup_only: BitVec,
+    startup_finished: bool,
+    generation: u64,
+    load_history: VecDeque<bundle::LoadRecord>,
+    scheduled: Vec<schedule::Changeset>,
+    name_index: HashMap<String, usize>,
+    slots: HashMap<Discriminant<T>, usize>,
+    #[cfg(feature = "yaml")]
+    load_options: load::LoadOptions,
+    exposure_sink: Option<Arc<dyn ExposureSink>>,
+    keep_backup: bool,
     _marker: std::marker::PhantomData<T>,
 }
 
@@ -79,6 +248,32 @@ where
     fn default() -> Self {
         EnumToggles {
             toggles_value: bitvec![0; T::iter().count()],
+            defaults: bitvec![0; T::iter().count()],
+            declared: bitvec![0; T::iter().count()],
+            previous: None,
+            previous_rollouts: vec![None; T::iter().count()],
+            provenance: vec![Provenance::Default; T::iter().count()],
+            changed_at: vec![None; T::iter().count()],
+            metadata: vec![ToggleMetadata::default(); T::iter().count()],
+            trips: vec![None; T::iter().count()],
+            rollouts: vec![None; T::iter().count()],
+            startup_only: bitvec![0; T::iter().count()],
+            startup_finished: false,
+            generation: 0,
+            load_history: VecDeque::new(),
+            scheduled: Vec::new(),
+            name_index: T::iter()
+                .enumerate()
+                .map(|(toggle_id, toggle)| (toggle.as_ref().to_string(), toggle_id))
+                .collect(),
+            slots: T::iter()
+                .enumerate()
+                .map(|(toggle_id, toggle)| (mem::discriminant(&toggle), toggle_id))
+                .collect(),
+            #[cfg(feature = "yaml")]
+            load_options: load::LoadOptions::default(),
+            exposure_sink: None,
+            keep_backup: false,
             _marker: std::marker::PhantomData,
         }
     }
@@ -93,59 +288,222 @@ where
     ///
     /// This operation is *O*(*n*).
     pub fn new() -> Self {
-        let mut toggles: EnumToggles<T> = EnumToggles {
-            toggles_value: bitvec![0; T::iter().count()],
-            _marker: std::marker::PhantomData,
-        };
+        let mut toggles: EnumToggles<T> = EnumToggles::default();
         toggles.toggles_value.fill(false);
         toggles
     }
 
-    /// Set all toggles value defiend in the yaml file.
-    pub fn load_from_file(&mut self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(filepath)?;
-        let docs = YamlLoader::load_from_str(&content)?;
-        let doc = &docs[0];
+    /// Create a new instance of `EnumToggles` with the defaults given by `f`, e.g. kill-switches
+    /// on and experiments off, every toggle starting at its default.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn with_defaults(f: impl Fn(&T) -> bool) -> Self {
+        let mut toggles = Self::new();
+        for (toggle_id, toggle) in T::iter().enumerate() {
+            let value = f(&toggle);
+            toggles.defaults.set(toggle_id, value);
+            toggles.toggles_value.set(toggle_id, value);
+        }
+        toggles
+    }
+
+    /// Set the default value of a toggle by toggle id, used by `reset` and `reset_all`.
+    ///
+    /// The current value is left untouched.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn set_default(&mut self, toggle_id: usize, value: bool) {
+        if toggle_id >= self.defaults.len() {
+            panic!(
+                "Out-of-bounds access. The provided toggle_id is {}, but the array size is {}. Please use the default enum value.",
+                toggle_id,
+                self.defaults.len()
+            );
+        }
+        self.defaults.set(toggle_id, value);
+    }
+
+    /// Restore the default value of a toggle.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn reset(&mut self, toggle: T) {
+        let toggle_id = self.toggle_id(&toggle);
+        let value = self.defaults[toggle_id];
+        self.assign(toggle_id, value, Provenance::Default);
+    }
+
+    /// Restore the default value of all toggles.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn reset_all(&mut self) {
+        let defaults = self.defaults.clone();
+        self.replace_values(&defaults, Provenance::Default);
+    }
+
+    /// Restore the default value of all toggles, same as `reset_all`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn reset_to_defaults(&mut self) {
+        self.reset_all();
+    }
+
+    /// Disable all toggles.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn clear(&mut self) {
+        let values = bitvec![0; self.toggles_value.len()];
+        self.replace_values(&values, Provenance::Api);
+    }
+
+    /// Enable all toggles.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn set_all_true(&mut self) {
+        let values = bitvec![1; self.toggles_value.len()];
+        self.replace_values(&values, Provenance::Api);
+    }
 
-        if let Yaml::Hash(ref h) = doc {
-            for (key, value) in h {
-                self.set_by_name(
-                    key.as_str().ok_or("Invalid key: not a string")?,
-                    value.as_i64().ok_or("Invalid value: not an integer")? == 1,
-                );
+    /// Restore the values and rollouts held before the last file load, so a bad config can be
+    /// undone while the upstream fix is on its way. Only the last load can be reverted,
+    /// returns false if there is nothing to revert.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn revert_last_load(&mut self) -> bool {
+        match self.previous.take() {
+            Some(previous) => {
+                self.replace_values(&previous, Provenance::Reverted);
+                for (toggle_id, buckets) in self.previous_rollouts.clone().into_iter().enumerate() {
+                    if let Some(buckets) = buckets {
+                        self.set_rollout_by_id(toggle_id, buckets, Provenance::Reverted);
+                    }
+                }
+                true
             }
+            None => false,
         }
+    }
 
-        Ok(())
+    fn replace_values(&mut self, values: &BitSlice, provenance: Provenance) {
+        for (toggle_id, value) in values.iter().by_vals().enumerate() {
+            self.assign(toggle_id, value, provenance.clone());
+        }
     }
+
+    /// Names of the toggles in declaration order, for tooling that doesn't have the enum in
+    /// scope. The enum derives strum's `IntoStaticStr` along with `AsRefStr`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn names(&self) -> Vec<&'static str>
+    where
+        T: Into<&'static str>,
+    {
+        T::iter().map(Into::into).collect()
+    }
+
+    /// Number of toggles.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn len(&self) -> usize {
+        self.toggles_value.len()
+    }
+
+    /// True if the enum has no variant.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn is_empty(&self) -> bool {
+        self.toggles_value.is_empty()
+    }
+
+    /// Number of files and environment variables successfully loaded so far.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Stable hash of the variant names in order, identifying the enum across processes.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn fingerprint() -> u64 {
+        let names: Vec<T> = T::iter().collect();
+        let joined = names
+            .iter()
+            .map(|t| t.as_ref())
+            .collect::<Vec<_>>()
+            .join("\n");
+        hash::fnv1a(joined.as_bytes())
+    }
+
     /// Set the bool value of all toggles based on a HashMap.
     ///
-    /// This operation is *O*(*n²*).
+    /// This operation is *O*(*n*).
     pub fn set_all(&mut self, init: HashMap<String, bool>) {
-        self.toggles_value.fill(false);
-        for toggle in T::iter() {
-            if init.contains_key(toggle.as_ref()) {
-                if let Some(toggle_id) = T::iter().position(|x| x == toggle) {
-                    self.set(toggle_id, init[toggle.as_ref()]);
-                }
-            }
+        for (toggle_id, toggle) in T::iter().enumerate() {
+            let value = init.get(toggle.as_ref()).copied().unwrap_or(false);
+            self.set(toggle_id, value);
         }
     }
 
     /// Set the bool value of a toggle by its name.
     ///
-    /// This operation is *O*(*n*).
+    /// This operation is *O*(*1*).
     pub fn set_by_name(&mut self, toggle_name: &str, value: bool) {
-        if let Some(toggle) = T::iter().find(|t| toggle_name == t.as_ref()) {
-            if let Some(toggle_id) = T::iter().position(|x| x == toggle) {
-                self.set(toggle_id, value);
-            }
+        if let Some(toggle_id) = self.id_by_name(toggle_name) {
+            self.set(toggle_id, value);
         }
     }
 
-    /// Set the bool value of a toggle by toggle id.
+    /// Set several toggles in one call, returning how many were applied, i.e. not rejected
+    /// as startup-only.
+    ///
+    /// This operation is *O*(*m*).
+    pub fn set_many(&mut self, pairs: &[(T, bool)]) -> usize {
+        pairs
+            .iter()
+            .filter(|(toggle, value)| self.assign(self.toggle_id(toggle), *value, Provenance::Api))
+            .count()
+    }
+
+    /// Set several toggles by name in one call, returning how many were applied. Unknown names
+    /// and startup-only toggles are skipped.
+    ///
+    /// This operation is *O*(*m*).
+    pub fn set_many_by_name(&mut self, pairs: &[(&str, bool)]) -> usize {
+        pairs
+            .iter()
+            .filter(|(name, value)| {
+                self.id_by_name(name)
+                    .is_some_and(|toggle_id| self.assign(toggle_id, *value, Provenance::Api))
+            })
+            .count()
+    }
+
+    /// Get the bool value of a toggle by its name, `None` if no toggle has this name.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn get_by_name(&self, toggle_name: &str) -> Option<bool> {
+        self.id_by_name(toggle_name)
+            .map(|toggle_id| self.get(toggle_id))
+    }
+
+    /// Toggle id of a name or alias, looked up in the index built at construction.
+    pub(crate) fn id_by_name(&self, toggle_name: &str) -> Option<usize> {
+        self.name_index.get(toggle_name).copied()
+    }
+
+    /// Accept `alias` as another name of `toggle`, in files and by-name accessors, e.g. to
+    /// keep the old config key of a renamed toggle working.
     ///
     /// This operation is *O*(*1*).
+    pub fn add_alias(&mut self, alias: &str, toggle: T) {
+        if T::iter().any(|t| t.as_ref() == alias) {
+            panic!("Alias {} is already the name of a toggle", alias);
+        }
+        let toggle_id = self.toggle_id(&toggle);
+        self.name_index.insert(alias.to_string(), toggle_id);
+    }
+
+    /// Set the bool value of a toggle by toggle id.
+    ///
+    /// This operation is *O*(*1*), *O*(*n*) on change with the `metrics` feature.
     pub fn set(&mut self, toggle_id: usize, value: bool) {
         if toggle_id >= self.toggles_value.len() {
             panic!(
@@ -154,7 +512,26 @@ where
                 self.toggles_value.len()
             );
         }
+        self.assign(toggle_id, value, Provenance::Api);
+    }
+
+    /// Set the value and provenance of a toggle, unless it is a startup-only toggle changed
+    /// after startup. Returns false if the change is rejected.
+    pub(crate) fn assign(&mut self, toggle_id: usize, value: bool, provenance: Provenance) -> bool {
+        if self.rejects_change(toggle_id, value) {
+            return false;
+        }
+        if self.toggles_value[toggle_id] != value {
+            self.changed_at[toggle_id] = Some(SystemTime::now());
+            #[cfg(feature = "metrics")]
+            if let Some(toggle) = T::iter().nth(toggle_id) {
+                gauges::publish(toggle.as_ref(), value);
+            }
+        }
+        self.provenance[toggle_id] = provenance;
         self.toggles_value.set(toggle_id, value);
+        self.rollouts[toggle_id] = None;
+        true
     }
 
     /// Get the bool value of a toggle by toggle id.
@@ -163,6 +540,535 @@ where
     pub fn get(&self, toggle_id: usize) -> bool {
         self.toggles_value[toggle_id]
     }
+
+    /// List the toggles whose value differs in `other`, as `(toggle, self value, other value)`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn diff(&self, other: &Self) -> Vec<ToggleChange<T>> {
+        T::iter()
+            .enumerate()
+            .filter(|(toggle_id, _)| self.get(*toggle_id) != other.get(*toggle_id))
+            .map(|(toggle_id, toggle)| (toggle, self.get(toggle_id), other.get(toggle_id)))
+            .collect()
+    }
+
+    /// Export the current state as a map of toggle names sorted by name, to hand it to other
+    /// config layers or compare it against a source file.
+    ///
+    /// This operation is *O*(*n* log *n*).
+    pub fn to_map(&self) -> BTreeMap<String, bool> {
+        T::iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| (toggle.as_ref().to_string(), self.get(toggle_id)))
+            .collect()
+    }
+
+    /// Set the bool value of a toggle by toggle id, failing instead of panicking on an
+    /// out-of-bounds id coming from dynamic data, and failing when the change of a
+    /// startup-only toggle is rejected after startup.
+    ///
+    /// This operation is *O*(*1*), *O*(*n*) when the change is rejected.
+    pub fn try_set(&mut self, toggle_id: usize, value: bool) -> Result<(), ToggleError> {
+        if toggle_id >= self.toggles_value.len() {
+            return Err(ToggleError::OutOfBounds {
+                toggle_id,
+                len: self.toggles_value.len(),
+            });
+        }
+        if self.assign(toggle_id, value, Provenance::Api) {
+            return Ok(());
+        }
+        let name = T::iter()
+            .nth(toggle_id)
+            .map_or_else(String::new, |toggle| toggle.as_ref().to_string());
+        Err(ToggleError::StartupOnly(name))
+    }
+
+    /// Get the bool value of a toggle by toggle id, `None` if the id is out of bounds.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn try_get(&self, toggle_id: usize) -> Option<bool> {
+        self.toggles_value.get(toggle_id).map(|value| *value)
+    }
+
+    /// Get the bool value of a toggle, mapped to its position in the enum so explicit
+    /// discriminants are handled.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn get_variant(&self, toggle: T) -> bool {
+        self.get(self.toggle_id(&toggle))
+    }
+
+    /// Set the bool value of a toggle, mapped to its position in the enum so explicit
+    /// discriminants are handled.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn set_variant(&mut self, toggle: T, value: bool) {
+        self.set(self.toggle_id(&toggle), value);
+    }
+
+    /// Iterate over the toggles currently enabled, e.g. to log the active feature set.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn enabled(&self) -> impl Iterator<Item = T> + '_ {
+        T::iter()
+            .enumerate()
+            .filter(|(toggle_id, _)| self.get(*toggle_id))
+            .map(|(_, toggle)| toggle)
+    }
+
+    /// Iterate over the toggles currently disabled.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn disabled(&self) -> impl Iterator<Item = T> + '_ {
+        T::iter()
+            .enumerate()
+            .filter(|(toggle_id, _)| !self.get(*toggle_id))
+            .map(|(_, toggle)| toggle)
+    }
+
+    /// Number of toggles currently enabled.
+    ///
+    /// This operation is *O*(*n*), counting the bits a word at a time.
+    pub fn count_enabled(&self) -> usize {
+        self.toggles_value.count_ones()
+    }
+
+    /// True if at least one toggle is enabled.
+    ///
+    /// This operation is *O*(*n*), checking the bits a word at a time.
+    pub fn any(&self) -> bool {
+        self.toggles_value.any()
+    }
+
+    /// True if all toggles are enabled.
+    ///
+    /// This operation is *O*(*n*), checking the bits a word at a time.
+    pub fn all(&self) -> bool {
+        self.toggles_value.all()
+    }
+
+    /// Flip the value of a toggle by toggle id, returning its new value.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn toggle(&mut self, toggle_id: usize) -> bool {
+        let value = !self.get(toggle_id);
+        self.set(toggle_id, value);
+        self.get(toggle_id)
+    }
+
+    /// Flip the value of a toggle, returning its new value.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn toggle_variant(&mut self, toggle: T) -> bool {
+        self.toggle(self.toggle_id(&toggle))
+    }
+
+    /// Get the values of several toggles by toggle id in one pass over the underlying words,
+    /// with a single bounds check, for hot loops.
+    ///
+    /// This operation is *O*(*N*).
+    pub fn get_many<const N: usize>(&self, toggle_ids: [usize; N]) -> [bool; N] {
+        let max = toggle_ids.iter().copied().max().unwrap_or(0);
+        if N > 0 && max >= self.toggles_value.len() {
+            panic!(
+                "Out-of-bounds access. The provided toggle_id is {}, but the array size is {}. Please use the default enum value.",
+                max,
+                self.toggles_value.len()
+            );
+        }
+        let words = self.toggles_value.as_raw_slice();
+        toggle_ids.map(|toggle_id| {
+            words[toggle_id / usize::BITS as usize] & (1 << (toggle_id % usize::BITS as usize)) != 0
+        })
+    }
+
+    /// Get the values of several toggles, see `get_many`.
+    ///
+    /// This operation is *O*(*N*).
+    pub fn is_enabled_many<const N: usize>(&self, toggles: [T; N]) -> [bool; N] {
+        self.get_many(toggles.map(|toggle| self.toggle_id(&toggle)))
+    }
+
+    /// Stable hash of the variant names, their values and rollout percentages, usable in cache
+    /// keys and ETags. It changes whenever a toggle value or rollout changes and is identical
+    /// across processes.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn state_hash(&self) -> u64 {
+        let mut state = String::new();
+        for (toggle_id, toggle) in T::iter().enumerate() {
+            state.push_str(toggle.as_ref());
+            state.push(if self.get(toggle_id) { '1' } else { '0' });
+            if let Some(buckets) = self.rollouts[toggle_id] {
+                state.push_str(&format!("@{}", buckets));
+            }
+            state.push('\n');
+        }
+        hash::fnv1a(state.as_bytes())
+    }
+
+    /// Export a minimal versioned JSON document meant to be uploaded to a CDN and read by
+    /// browser or mobile clients:
+    /// `{"version":1,"generated_at":<unix seconds>,"hash":"<state_hash>","toggles":{"FeatureA":true}}`.
+    ///
+    /// The hash is a hex string since a `u64` doesn't fit in a JavaScript number.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn export_client_snapshot(&self) -> String {
+        let generated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let toggles: Vec<String> = T::iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| {
+                format!("{}:{}", json::quote(toggle.as_ref()), self.get(toggle_id))
+            })
+            .collect();
+        format!(
+            "{{\"version\":1,\"generated_at\":{},\"hash\":\"{:016x}\",\"toggles\":{{{}}}}}",
+            generated_at,
+            self.state_hash(),
+            toggles.join(",")
+        )
+    }
+
+    /// Export all toggles as environment variable assignments, `FeatureA` becoming
+    /// `<prefix>FEATURE_A` with the value `1` or `0`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn to_env(&self, prefix: &str) -> Vec<(String, String)> {
+        T::iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| {
+                (
+                    format!(
+                        "{}{}",
+                        prefix,
+                        naming::to_screaming_snake_case(toggle.as_ref())
+                    ),
+                    (self.get(toggle_id) as u8).to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Write all toggles in the `.env` format, one `<prefix>FEATURE_A=1` line per toggle.
+    pub fn write_env<W: io::Write>(&self, prefix: &str, mut writer: W) -> io::Result<()> {
+        for (key, value) in self.to_env(prefix) {
+            writeln!(writer, "{}={}", key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Export all toggles as a bitflags-style mask, bit `i` holding the toggle id `i`.
+    ///
+    /// Panics if the enum has more variants than the mask has bits.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn to_mask<M: BitMask>(&self) -> M {
+        Self::check_mask_size::<M>(self.toggles_value.len());
+        let mask = self
+            .toggles_value
+            .iter_ones()
+            .fold(0u128, |mask, toggle_id| mask | (1 << toggle_id));
+        M::from_u128(mask)
+    }
+
+    /// Create a new instance of `EnumToggles` from a bitflags-style mask, bits beyond the
+    /// last toggle are ignored.
+    ///
+    /// Panics if the enum has more variants than the mask has bits.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn from_mask<M: BitMask>(mask: M) -> Self {
+        let mut toggles = Self::new();
+        Self::check_mask_size::<M>(toggles.toggles_value.len());
+        let mask = mask.to_u128();
+        for toggle_id in 0..toggles.toggles_value.len() {
+            toggles.set(toggle_id, mask & (1 << toggle_id) != 0);
+        }
+        toggles
+    }
+
+    /// Export the state as a `u128` bitmask, bit `i` holding the toggle of position `i`.
+    ///
+    /// Panics if the enum has more than 128 toggles, use `as_words` for large enums.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn as_bits(&self) -> u128 {
+        self.to_mask::<u128>()
+    }
+
+    /// Create a new instance of `EnumToggles` from a mask exported by `as_bits`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn from_bits(bits: u128) -> Self {
+        Self::from_mask(bits)
+    }
+
+    /// Export the state as 64-bit words of any length, bit `i % 64` of word `i / 64` holding
+    /// the toggle of position `i`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn as_words(&self) -> Vec<u64> {
+        let mut words = vec![0u64; self.toggles_value.len().div_ceil(64)];
+        for toggle_id in self.toggles_value.iter_ones() {
+            words[toggle_id / 64] |= 1 << (toggle_id % 64);
+        }
+        words
+    }
+
+    /// Create a new instance of `EnumToggles` from words exported by `as_words`, bits beyond
+    /// the last toggle being ignored.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn from_words(words: &[u64]) -> Self {
+        let mut toggles = Self::new();
+        let len = toggles.toggles_value.len();
+        if len > words.len() * 64 {
+            panic!(
+                "Mask too small. The enum has {} toggles, but the mask holds {} bits.",
+                len,
+                words.len() * 64
+            );
+        }
+        for toggle_id in 0..len {
+            toggles.set(
+                toggle_id,
+                words[toggle_id / 64] & (1 << (toggle_id % 64)) != 0,
+            );
+        }
+        toggles
+    }
+
+    fn check_mask_size<M: BitMask>(len: usize) {
+        if len > M::BITS as usize {
+            panic!(
+                "Mask too small. The enum has {} toggles, but the mask holds {} bits.",
+                len,
+                M::BITS
+            );
+        }
+    }
+
+    /// Send an exposure event to `sink` each time a toggle is read with `get_for` or
+    /// `rollout`.
+    pub fn set_exposure_sink(&mut self, sink: impl ExposureSink + 'static) {
+        self.exposure_sink = Some(Arc::new(sink));
+    }
+
+    /// Get the bool value of a toggle evaluated for a context key (user id, request id, ...),
+    /// deciding like `rollout` during a rollout and recording the exposure in the sink if any.
+    ///
+    /// This operation is *O*(*1*), *O*(*n*) plus the hash of the key during a rollout or when a
+    /// sink is set.
+    pub fn get_for(&self, toggle_id: usize, key: &str) -> bool {
+        if self.rollouts[toggle_id].is_none() && self.exposure_sink.is_none() {
+            return self.get(toggle_id);
+        }
+        match T::iter().nth(toggle_id) {
+            Some(toggle) => self.evaluate(toggle_id, toggle.as_ref(), key),
+            None => self.get(toggle_id),
+        }
+    }
+
+    /// Check combinations of toggles, typically right after the initial load, and list
+    /// every violated invariant in the error.
+    ///
+    /// This operation is *O*(*n* × *m*).
+    pub fn assert_invariants(&self, invariants: &[Invariant<T>]) -> Result<(), ToggleError> {
+        let mut violations = Vec::new();
+        for invariant in invariants {
+            match invariant {
+                Invariant::Exclusive(a, b) => {
+                    if self.is_enabled_ref(a) && self.is_enabled_ref(b) {
+                        violations.push(format!(
+                            "{} and {} are both enabled",
+                            a.as_ref(),
+                            b.as_ref()
+                        ));
+                    }
+                }
+                Invariant::Requires(a, b) => {
+                    if self.is_enabled_ref(a) && !self.is_enabled_ref(b) {
+                        violations.push(format!("{} requires {}", a.as_ref(), b.as_ref()));
+                    }
+                }
+                Invariant::Enabled(a) => {
+                    if !self.is_enabled_ref(a) {
+                        violations.push(format!("{} is disabled", a.as_ref()));
+                    }
+                }
+                Invariant::Declared(a) => {
+                    if !self.declared[self.toggle_id(a)] {
+                        violations.push(format!("{} is missing from the file", a.as_ref()));
+                    }
+                }
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ToggleError::InvariantViolations(violations))
+        }
+    }
+
+    fn is_enabled_ref(&self, toggle: &T) -> bool {
+        self.get(self.toggle_id(toggle))
+    }
+
+    /// Position of a toggle in the enum, whatever its discriminant (`Toggle = 5`).
+    ///
+    /// This operation is *O*(*1*).
+    fn toggle_id(&self, toggle: &T) -> usize {
+        self.slots[&mem::discriminant(toggle)]
+    }
+}
+
+impl<T> ReadToggles<T> for EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn is_enabled(&self, toggle: T) -> bool {
+        self.get(self.toggle_id(&toggle))
+    }
+}
+
+/// Read a toggle with `toggles[MyToggle::FeatureA]`.
+impl<T> std::ops::Index<T> for EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    type Output = bool;
+
+    fn index(&self, toggle: T) -> &bool {
+        if self.get_variant(toggle) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+/// Snapshot the values along with their defaults, provenance and history, the exposure
+/// sink being shared with the clone.
+impl<T> Clone for EnumToggles<T> {
+    fn clone(&self) -> Self {
+        EnumToggles {
+            toggles_value: self.toggles_value.clone(),
+            defaults: self.defaults.clone(),
+            declared: self.declared.clone(),
+            previous: self.previous.clone(),
+            previous_rollouts: self.previous_rollouts.clone(),
+            provenance: self.provenance.clone(),
+            changed_at: self.changed_at.clone(),
+            metadata: self.metadata.clone(),
+            trips: self.trips.clone(),
+            rollouts: self.rollouts.clone(),
+            startup_only: self.startup_only.clone(),
+            startup_finished: self.startup_finished,
+            generation: self.generation,
+            load_history: self.load_history.clone(),
+            scheduled: self.scheduled.clone(),
+            name_index: self.name_index.clone(),
+            slots: self.slots.clone(),
+            #[cfg(feature = "yaml")]
+            load_options: self.load_options.clone(),
+            exposure_sink: self.exposure_sink.clone(),
+            keep_backup: self.keep_backup,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Two toggle sets are equal if all their toggles have the same value and rollout, whatever
+/// their defaults, provenance or history.
+impl<T> PartialEq for EnumToggles<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.toggles_value == other.toggles_value && self.rollouts == other.rollouts
+    }
+}
+
+impl<T> Eq for EnumToggles<T> {}
+
+/// Hash the toggle values and rollouts, consistently with `PartialEq`.
+impl<T> std::hash::Hash for EnumToggles<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.toggles_value.hash(state);
+        self.rollouts.hash(state);
+    }
+}
+
+/// Build toggles from `(toggle, value)` pairs, the unlisted toggles being disabled.
+impl<T> FromIterator<(T, bool)> for EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn from_iter<I: IntoIterator<Item = (T, bool)>>(iter: I) -> Self {
+        let mut toggles = EnumToggles::new();
+        toggles.extend(iter);
+        toggles
+    }
+}
+
+/// Set the toggles of `(toggle, value)` pairs.
+impl<T> Extend<(T, bool)> for EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn extend<I: IntoIterator<Item = (T, bool)>>(&mut self, iter: I) {
+        for (toggle, value) in iter {
+            self.set_variant(toggle, value);
+        }
+    }
+}
+
+/// Build toggles from a map of names, failing on names that don't match any toggle.
+/// Toggles absent from the map are set to false.
+impl<T> TryFrom<HashMap<String, bool>> for EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    type Error = ToggleError;
+
+    fn try_from(init: HashMap<String, bool>) -> Result<Self, Self::Error> {
+        let mut toggles = Self::new();
+        let mut unknown_keys: Vec<String> = init
+            .keys()
+            .filter(|key| toggles.id_by_name(key).is_none())
+            .cloned()
+            .collect();
+        if !unknown_keys.is_empty() {
+            unknown_keys.sort();
+            return Err(ToggleError::UnknownKeys(unknown_keys));
+        }
+        toggles.set_all(init);
+        Ok(toggles)
+    }
+}
+
+/// Export all toggles as a map of names.
+impl<T> From<&EnumToggles<T>> for HashMap<String, bool>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn from(toggles: &EnumToggles<T>) -> Self {
+        T::iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| (toggle.as_ref().to_string(), toggles.get(toggle_id)))
+            .collect()
+    }
+}
+
+impl<T> From<EnumToggles<T>> for HashMap<String, bool>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn from(toggles: EnumToggles<T>) -> Self {
+        HashMap::from(&toggles)
+    }
 }
 
 /// Diplay all toggles and their values.
@@ -184,11 +1090,12 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "yaml")]
     use std::io::Write;
     use strum::IntoEnumIterator;
-    use strum_macros::{AsRefStr, EnumIter};
+    use strum_macros::{AsRefStr, EnumIter, IntoStaticStr};
 
-    #[derive(AsRefStr, EnumIter, PartialEq)]
+    #[derive(AsRefStr, EnumIter, IntoStaticStr, PartialEq)]
     pub enum TestToggles {
         Toggle1,
         Toggle2,
@@ -204,48 +1111,352 @@ mod tests {
     fn test_set_all() {
         let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
         toggles.set_all(HashMap::from([("Toggle1".to_string(), true)]));
-        assert_eq!(toggles.get(TestToggles::Toggle1 as usize), true);
-        assert_eq!(toggles.get(TestToggles::Toggle2 as usize), false);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
     }
 
     #[test]
     fn test_set_by_name() {
         let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
-        assert_eq!(toggles.get(TestToggles::Toggle1 as usize), false);
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
         toggles.set_by_name("Toggle1", true);
-        assert_eq!(toggles.get(TestToggles::Toggle1 as usize), true);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
 
         toggles.set_by_name("Undefined_Toggle", true);
     }
 
+    #[test]
+    fn test_add_alias() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.add_alias("OldToggle1", TestToggles::Toggle1);
+        toggles.set_by_name("OldToggle1", true);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(toggles.get_by_name("OldToggle1"), Some(true));
+        assert_eq!(toggles.names(), vec!["Toggle1", "Toggle2"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Alias Toggle2 is already the name of a toggle")]
+    fn test_add_alias_of_toggle_name() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.add_alias("Toggle2", TestToggles::Toggle1);
+    }
+
+    #[test]
+    fn test_set_many() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let applied =
+            toggles.set_many(&[(TestToggles::Toggle1, true), (TestToggles::Toggle2, true)]);
+        assert_eq!(applied, 2);
+        assert!(toggles.all());
+
+        let applied = toggles.set_many_by_name(&[("Toggle2", false), ("Undefined_Toggle", true)]);
+        assert_eq!(applied, 1);
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+    }
+
+    #[test]
+    fn test_get_by_name() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(toggles.get_by_name("Toggle1"), Some(false));
+        assert_eq!(toggles.get_by_name("Toggle2"), Some(true));
+        assert_eq!(toggles.get_by_name("Undefined_Toggle"), None);
+    }
+
     #[test]
     fn test_display() {
         let toggles: EnumToggles<TestToggles> = EnumToggles::new();
-        assert_eq!(format!("{:?}", toggles).is_empty(), false);
+        assert!(!format!("{:?}", toggles).is_empty());
     }
 
     #[test]
-    fn test_load_from_file() {
-        // Create a temporary file
+    fn test_state_hash() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let initial = toggles.state_hash();
+        assert_eq!(initial, EnumToggles::<TestToggles>::new().state_hash());
+
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        assert_ne!(toggles.state_hash(), initial);
+        toggles.set(TestToggles::Toggle1 as usize, false);
+        assert_eq!(toggles.state_hash(), initial);
+
+        toggles.set_rollout(TestToggles::Toggle1, 10.0);
+        let rolled_out = toggles.state_hash();
+        assert_ne!(rolled_out, initial);
+        let mut other: EnumToggles<TestToggles> = EnumToggles::new();
+        other.set_rollout(TestToggles::Toggle1, 90.0);
+        assert_ne!(other.state_hash(), rolled_out);
+        assert!(other != toggles);
+    }
+
+    #[test]
+    fn test_export_client_snapshot() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        let snapshot = toggles.export_client_snapshot();
+        assert!(snapshot.starts_with("{\"version\":1,\"generated_at\":"));
+        assert!(snapshot.contains(&format!("\"hash\":\"{:016x}\"", toggles.state_hash())));
+        assert!(snapshot.ends_with("\"toggles\":{\"Toggle1\":false,\"Toggle2\":true}}"));
+    }
+
+    #[test]
+    fn test_to_env() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(
+            toggles.to_env("APP_"),
+            vec![
+                ("APP_TOGGLE1".to_string(), "0".to_string()),
+                ("APP_TOGGLE2".to_string(), "1".to_string())
+            ]
+        );
+
+        let mut output = Vec::new();
+        toggles.write_env("", &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "TOGGLE1=0\nTOGGLE2=1\n");
+    }
+
+    #[test]
+    fn test_mask() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(toggles.to_mask::<u8>(), 0b10);
+
+        let toggles: EnumToggles<TestToggles> = EnumToggles::from_mask(0b1101u64);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(toggles.to_mask::<u128>(), 0b01);
+    }
+
+    #[test]
+    fn test_bits_and_words() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(toggles.as_bits(), 0b10);
+        assert_eq!(EnumToggles::<TestToggles>::from_bits(0b10), toggles);
+        assert_eq!(toggles.as_words(), vec![0b10]);
+        assert_eq!(EnumToggles::<TestToggles>::from_words(&[0b110]), toggles);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mask too small")]
+    fn test_from_words_too_small() {
+        EnumToggles::<TestToggles>::from_words(&[]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let before: EnumToggles<TestToggles> = EnumToggles::new();
+        let mut after = before.clone();
+        after.set(TestToggles::Toggle2 as usize, true);
+        let changes = before.diff(&after);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0] == (TestToggles::Toggle2, false, true));
+        assert!(after.diff(&after.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_try_set_try_get() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert_eq!(toggles.try_set(TestToggles::Toggle2 as usize, true), Ok(()));
+        assert_eq!(toggles.try_get(TestToggles::Toggle2 as usize), Some(true));
+        assert_eq!(
+            toggles.try_set(5, true),
+            Err(ToggleError::OutOfBounds {
+                toggle_id: 5,
+                len: 2
+            })
+        );
+        assert_eq!(toggles.try_get(5), None);
+
+        toggles.mark_startup_only(TestToggles::Toggle2);
+        toggles.finish_startup();
+        assert_eq!(
+            toggles.try_set(TestToggles::Toggle2 as usize, false),
+            Err(ToggleError::StartupOnly("Toggle2".to_string()))
+        );
+        assert_eq!(toggles.try_get(TestToggles::Toggle2 as usize), Some(true));
+        assert_eq!(toggles.try_set(TestToggles::Toggle2 as usize, true), Ok(()));
+    }
+
+    #[test]
+    fn test_to_map() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(
+            toggles.to_map(),
+            BTreeMap::from([
+                ("Toggle1".to_string(), false),
+                ("Toggle2".to_string(), true)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_hash_map_conversions() {
+        let toggles =
+            EnumToggles::<TestToggles>::try_from(HashMap::from([("Toggle2".to_string(), true)]))
+                .unwrap();
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+
+        let map: HashMap<String, bool> = toggles.into();
+        assert_eq!(
+            map,
+            HashMap::from([
+                ("Toggle1".to_string(), false),
+                ("Toggle2".to_string(), true)
+            ])
+        );
+
+        let result = EnumToggles::<TestToggles>::try_from(HashMap::from([
+            ("Toggle1".to_string(), true),
+            ("VAR1".to_string(), true),
+        ]));
+        assert_eq!(
+            result.err(),
+            Some(ToggleError::UnknownKeys(vec!["VAR1".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_get_for_records_exposure() {
+        use std::sync::{Arc, Mutex};
+
+        let exposures = Arc::new(Mutex::new(Vec::new()));
+        let recorded = exposures.clone();
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert!(!toggles.get_for(TestToggles::Toggle1 as usize, "user-1"));
+
+        toggles.set_exposure_sink(move |exposure: &Exposure| {
+            recorded.lock().unwrap().push(exposure.clone())
+        });
+        assert!(toggles.get_for(TestToggles::Toggle2 as usize, "user-1"));
+
+        let exposures = exposures.lock().unwrap();
+        assert_eq!(exposures.len(), 1);
+        assert_eq!(exposures[0].toggle, "Toggle2");
+        assert!(exposures[0].decision);
+        assert_eq!(exposures[0].key_hash, hash::fnv1a(b"user-1"));
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_assert_invariants() {
         let mut temp_file =
             tempfile::NamedTempFile::new().expect("Unable to create temporary file");
-
-        // Write some data to the file
         writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
-        writeln!(temp_file, "Toggle2: 0").expect("Unable to write to temporary file");
-        writeln!(temp_file, "VAR1: 0").expect("Unable to write to temporary file");
-        writeln!(temp_file, "").expect("Unable to write to temporary file");
-
-        // Get the path of the temporary file
         let filepath = temp_file.path().to_str().unwrap();
 
-        // Create a Toggles instance and load from the file
         let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
-        let _ = toggles.load_from_file(filepath);
+        toggles.load_from_file(filepath).unwrap();
+        assert_eq!(toggles.generation(), 1);
+        assert!(toggles
+            .assert_invariants(&[
+                Invariant::Declared(TestToggles::Toggle1),
+                Invariant::Enabled(TestToggles::Toggle1),
+                Invariant::Exclusive(TestToggles::Toggle1, TestToggles::Toggle2),
+            ])
+            .is_ok());
 
-        // Verify that the toggles were set correctly
-        assert_eq!(toggles.get(TestToggles::Toggle1 as usize), true);
-        assert_eq!(toggles.get(TestToggles::Toggle2 as usize), false);
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(
+            toggles.assert_invariants(&[
+                Invariant::Exclusive(TestToggles::Toggle1, TestToggles::Toggle2),
+                Invariant::Requires(TestToggles::Toggle2, TestToggles::Toggle1),
+                Invariant::Declared(TestToggles::Toggle2),
+            ]),
+            Err(ToggleError::InvariantViolations(vec![
+                "Toggle1 and Toggle2 are both enabled".to_string(),
+                "Toggle2 is missing from the file".to_string(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_get_many() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(toggles.get_many([1, 0, 1]), [true, false, true]);
+        assert_eq!(toggles.get_many([]), [false; 0]);
+        assert_eq!(
+            toggles.is_enabled_many([TestToggles::Toggle1, TestToggles::Toggle2]),
+            [false, true]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Out-of-bounds access")]
+    fn test_get_many_out_of_bounds() {
+        let toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.get_many([0, 2]);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_default(TestToggles::Toggle2 as usize, true);
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.set(TestToggles::Toggle2 as usize, false);
+
+        toggles.reset(TestToggles::Toggle2);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+
+        toggles.set(TestToggles::Toggle2 as usize, false);
+        toggles.reset_all();
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+    }
+
+    #[test]
+    fn test_clear_and_set_all_true() {
+        let mut toggles: EnumToggles<TestToggles> =
+            EnumToggles::with_defaults(|toggle| *toggle == TestToggles::Toggle1);
+        toggles.set_all_true();
+        assert!(toggles.all());
+        toggles.clear();
+        assert!(!toggles.any());
+        toggles.reset_to_defaults();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+    }
+
+    #[test]
+    fn test_names_and_len() {
+        let toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert_eq!(toggles.names(), vec!["Toggle1", "Toggle2"]);
+        assert_eq!(toggles.len(), 2);
+        assert!(!toggles.is_empty());
+    }
+
+    #[test]
+    fn test_with_defaults() {
+        let mut toggles: EnumToggles<TestToggles> =
+            EnumToggles::with_defaults(|toggle| *toggle == TestToggles::Toggle2);
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(toggles.status()[1].provenance, Provenance::Default);
+
+        toggles.set(TestToggles::Toggle2 as usize, false);
+        toggles.reset_all();
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+    }
+
+    #[test]
+    fn test_deviant_toggles_by_variant() {
+        let mut toggles: EnumToggles<DeviantToggles> = EnumToggles::new();
+        toggles.set_variant(DeviantToggles::Toggle1, true);
+        toggles.reset(DeviantToggles::Toggle2);
+        assert!(toggles[DeviantToggles::Toggle1]);
+        assert!(toggles.is_enabled(DeviantToggles::Toggle1));
+        assert!(!toggles.toggle_variant(DeviantToggles::Toggle1));
+        assert_eq!(
+            toggles.is_enabled_many([DeviantToggles::Toggle1, DeviantToggles::Toggle2]),
+            [false, false]
+        );
     }
 
     #[derive(AsRefStr, EnumIter, PartialEq)]
@@ -262,4 +1473,95 @@ mod tests {
         let mut toggles: EnumToggles<DeviantToggles> = EnumToggles::new();
         toggles.set(DeviantToggles::Toggle1 as usize, true);
     }
+
+    #[test]
+    fn test_enabled_disabled() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        let enabled: Vec<String> = toggles.enabled().map(|t| t.as_ref().to_string()).collect();
+        let disabled: Vec<String> = toggles.disabled().map(|t| t.as_ref().to_string()).collect();
+        assert_eq!(enabled, vec!["Toggle2"]);
+        assert_eq!(disabled, vec!["Toggle1"]);
+    }
+
+    #[test]
+    fn test_count_any_all() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert_eq!(toggles.count_enabled(), 0);
+        assert!(!toggles.any());
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(toggles.count_enabled(), 1);
+        assert!(toggles.any());
+        assert!(!toggles.all());
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        assert!(toggles.all());
+    }
+
+    #[test]
+    fn test_from_iter_extend() {
+        let mut toggles: EnumToggles<TestToggles> =
+            [(TestToggles::Toggle2, true)].into_iter().collect();
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        toggles.extend([(TestToggles::Toggle1, true), (TestToggles::Toggle2, false)]);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+    }
+
+    #[test]
+    fn test_eq_hash() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let mut other: EnumToggles<TestToggles> = EnumToggles::new();
+        other.set_default(TestToggles::Toggle1 as usize, true);
+        assert_eq!(toggles, other);
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert_ne!(toggles, other);
+
+        let mut cache: HashMap<EnumToggles<TestToggles>, u32> = HashMap::new();
+        cache.insert(other, 1);
+        cache.insert(toggles, 2);
+        let mut lookup: EnumToggles<TestToggles> = EnumToggles::new();
+        lookup.set(TestToggles::Toggle2 as usize, true);
+        assert_eq!(cache.get(&lookup), Some(&2));
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        let snapshot = toggles.clone();
+        toggles.set(TestToggles::Toggle1 as usize, false);
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert!(snapshot.get(TestToggles::Toggle1 as usize));
+        assert!(!snapshot.get(TestToggles::Toggle2 as usize));
+        toggles = snapshot;
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(toggles.status()[0].provenance, Provenance::Api);
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert!(toggles.toggle(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.toggle_variant(TestToggles::Toggle1));
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+    }
+
+    #[test]
+    fn test_index() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert!(!toggles[TestToggles::Toggle1]);
+        assert!(toggles[TestToggles::Toggle2]);
+    }
+
+    #[test]
+    fn test_variant_accessors() {
+        let mut toggles: EnumToggles<DeviantToggles> = EnumToggles::new();
+        toggles.set_variant(DeviantToggles::Toggle2, true);
+        assert!(!toggles.get_variant(DeviantToggles::Toggle1));
+        assert!(toggles.get_variant(DeviantToggles::Toggle2));
+        assert!(toggles.get(1));
+    }
 }
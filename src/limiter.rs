@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Let a warning through once per key per interval, so a typo in a file reloaded on every
+/// poll doesn't flood the logs.
+#[derive(Clone)]
+pub(crate) struct WarnLimiter {
+    interval: Duration,
+    last_warned: HashMap<String, Instant>,
+}
+
+impl WarnLimiter {
+    pub(crate) fn new(interval: Duration) -> Self {
+        WarnLimiter {
+            interval,
+            last_warned: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// True if no warning was emitted for `key` during the last interval.
+    pub(crate) fn should_warn(&mut self, key: &str) -> bool {
+        let now = Instant::now();
+        match self.last_warned.get(key) {
+            Some(last) if now.duration_since(*last) < self.interval => false,
+            _ => {
+                self.last_warned.insert(key.to_string(), now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_warn() {
+        let mut limiter = WarnLimiter::new(Duration::from_secs(60));
+        assert!(limiter.should_warn("FeatureAA"));
+        assert!(!limiter.should_warn("FeatureAA"));
+        assert!(limiter.should_warn("FeatureBB"));
+
+        limiter.set_interval(Duration::ZERO);
+        assert!(limiter.should_warn("FeatureAA"));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{yaml, ToggleError};
+use std::io::Read;
+
+/// Bounds on the files read by the loaders, so a file from a shared volume can't exhaust the
+/// memory of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    max_file_size: usize,
+    max_keys: usize,
+    max_depth: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        ParseLimits {
+            max_file_size: 1024 * 1024,
+            max_keys: 10_000,
+            max_depth: 16,
+        }
+    }
+}
+
+impl ParseLimits {
+    /// Create a new instance of `ParseLimits` allowing 1 MiB files, 10 000 keys and 16
+    /// levels of nesting.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject files larger than `max_file_size` bytes.
+    pub fn max_file_size(mut self, max_file_size: usize) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Reject files declaring more than `max_keys` keys, those of nested namespaces included.
+    pub fn max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = max_keys;
+        self
+    }
+
+    /// Reject files nesting maps and sequences deeper than `max_depth`, a flat map being 1.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Read a document up to one byte past the size limit, so oversized documents are detected
+    /// without being read in full.
+    pub(crate) fn read(&self, reader: impl Read) -> Result<String, ToggleError> {
+        let mut content = String::new();
+        reader
+            .take(self.max_file_size as u64 + 1)
+            .read_to_string(&mut content)?;
+        Ok(content)
+    }
+
+    /// Check a document against the limits before building it, returning its shape.
+    pub(crate) fn check(&self, content: &str) -> Result<yaml::Shape, ToggleError> {
+        self.check_size(content)?;
+        let shape = yaml::shape(content)?;
+        self.check_key_count(shape.key_count)?;
+        if shape.max_depth > self.max_depth {
+            return Err(ToggleError::LimitExceeded(format!(
+                "nesting depth {}, at most {} allowed",
+                shape.max_depth, self.max_depth
+            )));
+        }
+        Ok(shape)
+    }
+
+    /// Check the size of a document of any format.
+    pub(crate) fn check_size(&self, content: &str) -> Result<(), ToggleError> {
+        if content.len() > self.max_file_size {
+            return Err(ToggleError::LimitExceeded(format!(
+                "more than {} bytes",
+                self.max_file_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check the number of keys of a document of any format.
+    pub(crate) fn check_key_count(&self, key_count: usize) -> Result<(), ToggleError> {
+        if key_count > self.max_keys {
+            return Err(ToggleError::LimitExceeded(format!(
+                "{} keys, at most {} allowed",
+                key_count, self.max_keys
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let limits = ParseLimits::new()
+            .max_file_size(64)
+            .max_keys(2)
+            .max_depth(1);
+        assert!(limits.check("FeatureA: 1\nFeatureB: 0\n").is_ok());
+        assert!(matches!(
+            limits.check(&"#".repeat(65)),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+        assert!(matches!(
+            limits.check("A: 1\nB: 1\nC: 1\n"),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+        assert!(matches!(
+            limits.check("A:\n  B: 1\n"),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+        assert!(matches!(
+            ParseLimits::new()
+                .max_keys(2)
+                .check("A:\n  B: 1\n  C: 1\n  D: 1\n"),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+    }
+}
@@ -0,0 +1,1601 @@
+use crate::json5;
+use crate::limiter::WarnLimiter;
+use crate::naming;
+use crate::rollout;
+use crate::{
+    yaml::{self, Setting},
+    EnumToggles, LoadReport, NamingConvention, ParseLimits, Provenance, SourceFormat, ToggleChange,
+    ToggleError,
+};
+use log::{debug, info, warn};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Entries with their wildcards expanded, and the number of toggles matched by each wildcard.
+type Expanded = (Vec<(String, Setting)>, Vec<(String, usize)>);
+
+/// Values and rollout percentages of a parsed file, in file order, along with the keys it
+/// declares more than once, the deprecated names it uses and the files it includes.
+#[derive(Default)]
+pub(crate) struct Document {
+    pub(crate) entries: Vec<(String, Setting)>,
+    /// Keys whose values the yaml loader dropped for a later declaration.
+    shadowed_keys: Vec<String>,
+    duplicate_keys: Vec<String>,
+    deprecated_keys: Vec<(String, String)>,
+    /// Keys breaking the naming convention, as written in the file.
+    naming_violations: Vec<String>,
+    includes: Vec<String>,
+    format: SourceFormat,
+}
+
+impl Document {
+    fn parse(
+        content: &str,
+        format: &SourceFormat,
+        limits: &ParseLimits,
+    ) -> Result<Self, ToggleError> {
+        let entries = match format {
+            SourceFormat::Yaml => return Self::parse_yaml(content, limits),
+            SourceFormat::Json => return Self::parse_yaml(&json5::strip(content), limits),
+            #[cfg(feature = "toml")]
+            SourceFormat::Toml(table) => {
+                limits.check_size(content)?;
+                crate::toml::parse(content, table.as_deref())?
+            }
+            SourceFormat::Dotenv(prefix) => {
+                limits.check_size(content)?;
+                crate::dotenv::parse(content, prefix)?
+            }
+            SourceFormat::Ini => {
+                limits.check_size(content)?;
+                crate::ini::parse(content)?
+            }
+            SourceFormat::Properties => {
+                limits.check_size(content)?;
+                crate::properties::parse(content)?
+            }
+            #[cfg(feature = "ron")]
+            SourceFormat::Ron => {
+                limits.check_size(content)?;
+                crate::ron::parse(content)?
+            }
+        };
+        limits.check_key_count(entries.len())?;
+        Ok(Document {
+            entries: entries
+                .into_iter()
+                .map(|(key, value)| (key, Setting::Value(value)))
+                .collect(),
+            ..Document::default()
+        })
+    }
+
+    fn parse_yaml(content: &str, limits: &ParseLimits) -> Result<Self, ToggleError> {
+        let shape = limits.check(content)?;
+        let values = yaml::parse_values(content)?;
+        Ok(Document {
+            entries: values.entries,
+            shadowed_keys: shape.shadowed_keys,
+            duplicate_keys: Vec::new(),
+            deprecated_keys: Vec::new(),
+            naming_violations: Vec::new(),
+            includes: values.includes,
+            format: SourceFormat::Yaml,
+        })
+    }
+
+    /// Append the keys of a document read after this one, overriding its values.
+    pub(crate) fn append(&mut self, other: Document) {
+        self.entries.extend(other.entries);
+        self.duplicate_keys.extend(other.duplicate_keys);
+        self.deprecated_keys.extend(other.deprecated_keys);
+        self.naming_violations.extend(other.naming_violations);
+    }
+}
+
+/// Loader of the last file, run again by `reload`.
+#[derive(Clone)]
+pub(crate) enum Loader {
+    /// `load_from_file`, detecting the format again.
+    Detected,
+    /// `load_from_file_strict`.
+    Strict,
+    /// One of the `load_from_*` loaders of a given format.
+    Format,
+    /// `load_from_file_for_env` with the given profile.
+    Profile(String),
+}
+
+/// Path, format and loader of the last file loaded.
+#[derive(Clone)]
+struct Source {
+    path: String,
+    format: SourceFormat,
+    loader: Loader,
+}
+
+/// Settings of the file loaders.
+#[derive(Clone)]
+pub(crate) struct LoadOptions {
+    naming_convention: NamingConvention,
+    warn_limiter: WarnLimiter,
+    local_override: Option<String>,
+    parse_limits: ParseLimits,
+    loose_matching: bool,
+    deprecated_names: HashSet<String>,
+    source: Option<Source>,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            naming_convention: NamingConvention::new(),
+            warn_limiter: WarnLimiter::new(Duration::from_secs(60)),
+            local_override: None,
+            parse_limits: ParseLimits::default(),
+            loose_matching: false,
+            deprecated_names: HashSet::new(),
+            source: None,
+        }
+    }
+}
+
+/// Load the toggle values from files.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Set all toggles value defiend in the yaml file, reporting the applied, unknown,
+    /// missing, duplicated and changed keys. A key declared twice is warned about, the last
+    /// value wins. The file and its local override are parsed in full before anything is
+    /// applied, on error the previous values, generation and load history are left untouched.
+    /// JSON, TOML, `.env`, INI, properties and RON files are recognized by their extension,
+    /// or the look of their first line, and read like their `load_from_*` counterpart.
+    pub fn load_from_file(&mut self, filepath: &str) -> Result<LoadReport, ToggleError> {
+        let document = self.read_with_local_override(filepath)?;
+        self.set_source(filepath, &document.format, Loader::Detected);
+        Ok(self.apply_document(document, Provenance::File(filepath.to_string()), filepath))
+    }
+
+    /// Set all toggles value defined in a JSON or JSON5 file, e.g. `{"FeatureA": true}`, with
+    /// the same reporting as `load_from_file`. Comments and trailing commas are accepted so
+    /// hand-edited files can explain why a toggle is on. Plain JSON documents are yaml ones,
+    /// so `load_from_file` accepts them as well.
+    pub fn load_from_json(&mut self, filepath: &str) -> Result<LoadReport, ToggleError> {
+        self.load_file_as(filepath, &SourceFormat::Json)
+    }
+
+    /// Set all toggles value defined in a TOML file, e.g. `FeatureA = true`, with the same
+    /// reporting as `load_from_file`. With `table`, e.g. `Some("toggles")`, only the keys of
+    /// this table are read, so the toggles can live in a larger application config file.
+    #[cfg(feature = "toml")]
+    pub fn load_from_toml(
+        &mut self,
+        filepath: &str,
+        table: Option<&str>,
+    ) -> Result<LoadReport, ToggleError> {
+        self.load_file_as(filepath, &SourceFormat::Toml(table.map(str::to_string)))
+    }
+
+    /// Set all toggles value defined in a `.env` file, e.g. `APP_FEATURE_A=1` for the prefix
+    /// `APP_`, with the same reporting as `load_from_file`. Only the keys starting with
+    /// `prefix` are read, the rest of the file is left to the application. Once the prefix is
+    /// stripped, keys match the toggle names either as is or in `SCREAMING_SNAKE_CASE`, see
+    /// `set_loose_matching` for other spellings.
+    pub fn load_from_dotenv(
+        &mut self,
+        filepath: &str,
+        prefix: &str,
+    ) -> Result<LoadReport, ToggleError> {
+        self.load_file_as(filepath, &SourceFormat::Dotenv(prefix.to_string()))
+    }
+
+    /// Set all toggles value defined in an INI file, with the same reporting as
+    /// `load_from_file`. Section names prefix the keys of their section, e.g.
+    /// ```ini
+    /// [Checkout]
+    /// NewFlow = 1
+    /// ```
+    /// sets `CheckoutNewFlow`.
+    pub fn load_from_ini(&mut self, filepath: &str) -> Result<LoadReport, ToggleError> {
+        self.load_file_as(filepath, &SourceFormat::Ini)
+    }
+
+    /// Set all toggles value defined in a Java `.properties` file, e.g. `FeatureA=1`, with the
+    /// same reporting as `load_from_file`.
+    pub fn load_from_properties(&mut self, filepath: &str) -> Result<LoadReport, ToggleError> {
+        self.load_file_as(filepath, &SourceFormat::Properties)
+    }
+
+    /// Set all toggles value defined in a RON file, e.g. `(FeatureA: true)`, with the same
+    /// reporting as `load_from_file`.
+    #[cfg(feature = "ron")]
+    pub fn load_from_ron(&mut self, filepath: &str) -> Result<LoadReport, ToggleError> {
+        self.load_file_as(filepath, &SourceFormat::Ron)
+    }
+
+    /// Set all toggles value defined in a document held in memory, e.g. an HTTP body or an
+    /// `include_str!`, with the same reporting as `load_from_file`.
+    pub fn load_from_str(
+        &mut self,
+        content: &str,
+        format: SourceFormat,
+    ) -> Result<LoadReport, ToggleError> {
+        let document = self.parse_document(content, &format)?;
+        Ok(self.apply_document(document, Provenance::Api, "string"))
+    }
+
+    /// Set all toggles value defined in a document streamed from `reader`, e.g. a socket, an
+    /// archive entry or stdin, with the same reporting as `load_from_file`. The reader is
+    /// consumed up to one byte past the size limit.
+    pub fn load_from_reader(
+        &mut self,
+        reader: impl Read,
+        format: SourceFormat,
+    ) -> Result<LoadReport, ToggleError> {
+        let content = self.read_bounded(reader)?;
+        let document = self.parse_document(&content, &format)?;
+        Ok(self.apply_document(document, Provenance::Api, "reader"))
+    }
+
+    /// Load again the last file loaded, with the loader and format it was loaded with,
+    /// returning the toggles it changed as `(toggle, old value, new value)` so applications
+    /// can react precisely, e.g. invalidate a cache or reconnect.
+    pub fn reload(&mut self) -> Result<Vec<ToggleChange<T>>, ToggleError> {
+        let source = self
+            .load_options
+            .source
+            .clone()
+            .ok_or_else(|| ToggleError::Load("No file loaded yet".to_string()))?;
+        let before = self.toggles_value.clone();
+        match source.loader {
+            Loader::Detected => self.load_from_file(&source.path)?,
+            Loader::Strict => self.load_from_file_strict(&source.path)?,
+            Loader::Format => self.load_file_as(&source.path, &source.format)?,
+            Loader::Profile(profile) => self.load_from_file_for_env(&source.path, &profile)?,
+        };
+        Ok(T::iter()
+            .enumerate()
+            .filter(|(toggle_id, _)| before[*toggle_id] != self.get(*toggle_id))
+            .map(|(toggle_id, toggle)| (toggle, before[toggle_id], self.get(toggle_id)))
+            .collect())
+    }
+
+    /// Set all toggles value defined in the yaml file, failing without applying anything if
+    /// the file holds keys that don't match any toggle, e.g. a `FeatureAA` typo, or keys
+    /// declared more than once.
+    pub fn load_from_file_strict(&mut self, filepath: &str) -> Result<LoadReport, ToggleError> {
+        let document = self.read_with_local_override(filepath)?;
+        if !document.duplicate_keys.is_empty() {
+            return Err(ToggleError::DuplicateKeys(document.duplicate_keys));
+        }
+        let (expanded, _) = Self::expand_wildcards(document.entries.clone());
+        let unknown_keys: Vec<String> = expanded
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| self.id_by_name(name).is_none())
+            .collect();
+        if !unknown_keys.is_empty() {
+            return Err(ToggleError::UnknownKeys(unknown_keys));
+        }
+        self.set_source(filepath, &document.format, Loader::Strict);
+        Ok(self.apply_document(document, Provenance::File(filepath.to_string()), filepath))
+    }
+
+    /// Set all toggles value defined in a yaml or JSON document held by one environment
+    /// variable, e.g. `TOGGLES_JSON='{"FeatureA": 1}'`, for platforms where it is the only
+    /// way to inject configuration.
+    pub fn load_from_env_blob(&mut self, var: &str) -> Result<LoadReport, ToggleError> {
+        let content = env::var(var).map_err(|e| {
+            ToggleError::Load(format!("Environment variable {} unusable: {}", var, e))
+        })?;
+        let document = self.parse_document(&content, &SourceFormat::Yaml)?;
+        Ok(self.apply_document(document, Provenance::Env(var.to_string()), var))
+    }
+
+    /// Apply a parsed document and its rollout percentages, warning about and reporting its
+    /// duplicated and deprecated keys, and reporting the names breaking the convention.
+    pub(crate) fn apply_document(
+        &mut self,
+        document: Document,
+        provenance: Provenance,
+        source: &str,
+    ) -> LoadReport {
+        for key in &document.duplicate_keys {
+            warn!(
+                "Duplicate toggle {} in {}, the last value wins",
+                key, source
+            );
+        }
+        for (key, name) in &document.deprecated_keys {
+            warn!("Deprecated toggle {} in {}, renamed {}", key, source, name);
+        }
+        if !document.includes.is_empty() {
+            warn!(
+                "Ignored include in {}, only files can include others",
+                source
+            );
+        }
+        let naming_violations = self.naming_violations(&document.naming_violations);
+        let mut report = self.apply(document.entries, provenance, source);
+        report.naming_violations = naming_violations;
+        report.duplicate_keys = document.duplicate_keys;
+        report.deprecated_keys = document.deprecated_keys;
+        report
+    }
+
+    /// Apply parsed values and rollout percentages in file order, `source` naming the file or
+    /// variable in warnings. A toggle is reported as applied unless the change is rejected.
+    pub(crate) fn apply(
+        &mut self,
+        entries: Vec<(String, Setting)>,
+        provenance: Provenance,
+        source: &str,
+    ) -> LoadReport {
+        let (entries, wildcards) = Self::expand_wildcards(entries);
+        for (pattern, count) in &wildcards {
+            info!("{} matched {} toggles in {}", pattern, count, source);
+        }
+        let mut report = LoadReport {
+            wildcard_matches: wildcards,
+            ..LoadReport::default()
+        };
+        self.previous = Some(self.toggles_value.clone());
+        self.previous_rollouts = self.rollouts.clone();
+        self.declared.fill(false);
+        for (name, setting) in entries {
+            let Some(toggle_id) = self.id_by_name(&name) else {
+                if self.load_options.warn_limiter.should_warn(&name) {
+                    warn!("Unknown toggle {} in {}", name, source);
+                }
+                report.unknown_keys.push(name);
+                continue;
+            };
+            self.declared.set(toggle_id, true);
+            let (value, buckets) = target(setting);
+            if self.defer_tripped(toggle_id, value, buckets, &provenance) {
+                report.applied.push(name);
+                continue;
+            }
+            let changed = self.toggles_value[toggle_id] != value;
+            let accepted = match buckets {
+                Some(buckets) => self.set_rollout_by_id(toggle_id, buckets, provenance.clone()),
+                None => self.assign(toggle_id, value, provenance.clone()),
+            };
+            if accepted {
+                if changed {
+                    debug!("{} set to {} from {}", name, value, source);
+                    report.changed.push((name.clone(), !value, value));
+                }
+                report.applied.push(name);
+            }
+        }
+        report.missing_variants = T::iter()
+            .enumerate()
+            .filter(|(toggle_id, _)| !self.declared[*toggle_id])
+            .map(|(_, toggle)| toggle.as_ref().to_string())
+            .collect();
+        self.record_load(provenance);
+        info!(
+            "Loaded {} toggles from {}, generation {}",
+            report.applied.len(),
+            source,
+            self.generation
+        );
+        report
+    }
+
+    /// Check a yaml file against the enum without applying it.
+    pub fn validate_file(&self, filepath: &str) -> Result<LoadReport, ToggleError> {
+        let document = self.read_with_local_override(filepath)?;
+        let (entries, wildcards) = Self::expand_wildcards(document.entries);
+        let names: Vec<T> = T::iter().collect();
+        let mut report = LoadReport::new(
+            names.iter().map(|t| t.as_ref()),
+            entries.iter().map(|(key, _)| key.as_str()),
+        );
+        report.naming_violations = self.naming_violations(&document.naming_violations);
+        report.duplicate_keys = document.duplicate_keys;
+        report.deprecated_keys = document.deprecated_keys;
+        report.wildcard_matches = wildcards;
+        Ok(report)
+    }
+
+    /// Check that the yaml file declares every toggle exactly once and nothing else, so the
+    /// config and the enum can't drift apart. Meant for startup or CI checks.
+    pub fn validate_file_complete(&self, filepath: &str) -> Result<(), ToggleError> {
+        let report = self.validate_file(filepath)?;
+        if report.is_complete() {
+            Ok(())
+        } else {
+            Err(ToggleError::Incomplete {
+                missing_variants: report.missing_variants,
+                duplicate_keys: report.duplicate_keys,
+                unknown_keys: report.unknown_keys,
+            })
+        }
+    }
+
+    /// Bound the size, key count and nesting of the files and documents read by the loaders.
+    pub fn set_parse_limits(&mut self, limits: ParseLimits) {
+        self.load_options.parse_limits = limits;
+    }
+
+    /// Resolve file keys regardless of case, `_` and `-`, so `feature_a`, `feature-a` and
+    /// `FEATURE_A` all set `FeatureA`. Disabled by default.
+    pub fn set_loose_matching(&mut self, enabled: bool) {
+        self.load_options.loose_matching = enabled;
+    }
+
+    /// Rename the keys of the document that are aliases, or match a toggle once folded when
+    /// loose matching is enabled, to the toggle name.
+    fn resolve_keys(&self, document: &mut Document) {
+        let names: Vec<T> = T::iter().collect();
+        let keys = document
+            .entries
+            .iter_mut()
+            .map(|(key, _)| (key, true))
+            .chain(document.shadowed_keys.iter_mut().map(|key| (key, false)));
+        for (key, applied) in keys {
+            if naming::is_glob(key) {
+                continue;
+            }
+            if let Some(toggle_id) = self.resolve_key(key) {
+                let name = names[toggle_id].as_ref();
+                if applied && self.load_options.deprecated_names.contains(key.as_str()) {
+                    document
+                        .deprecated_keys
+                        .push((key.clone(), name.to_string()));
+                }
+                *key = name.to_string();
+            }
+        }
+    }
+
+    /// Toggle id of a file key: a toggle name, an alias, or a name matching once folded when
+    /// loose matching is enabled.
+    pub(crate) fn resolve_key(&self, key: &str) -> Option<usize> {
+        self.id_by_name(key).or_else(|| {
+            if !self.load_options.loose_matching {
+                return None;
+            }
+            let key_folded = naming::fold(key);
+            T::iter().position(|toggle| naming::fold(toggle.as_ref()) == key_folded)
+        })
+    }
+
+    /// Path and format of the last file loaded, written back by `save`. Files loaded for a
+    /// profile can't be written back, their values coming from several sections.
+    pub(crate) fn saved_source(&self) -> Result<(&str, &SourceFormat), ToggleError> {
+        let source = self
+            .load_options
+            .source
+            .as_ref()
+            .ok_or_else(|| ToggleError::Load("No file loaded yet".to_string()))?;
+        if let Loader::Profile(profile) = &source.loader {
+            return Err(ToggleError::Load(format!(
+                "{} was loaded for the profile {}, it can't be saved",
+                source.path, profile
+            )));
+        }
+        Ok((source.path.as_str(), &source.format))
+    }
+
+    /// Remember the last file loaded, for `reload` and `save`.
+    pub(crate) fn set_source(&mut self, filepath: &str, format: &SourceFormat, loader: Loader) {
+        self.load_options.source = Some(Source {
+            path: filepath.to_string(),
+            format: format.clone(),
+            loader,
+        });
+    }
+
+    /// Keep accepting the former name of a renamed toggle, warning about and reporting the
+    /// files still using it.
+    pub fn add_deprecated_name(&mut self, deprecated_name: &str, toggle: T) {
+        self.add_alias(deprecated_name, toggle);
+        self.load_options
+            .deprecated_names
+            .insert(deprecated_name.to_string());
+    }
+
+    /// Warn at most once per `interval` about the same unknown key, 60 seconds by default.
+    pub fn set_warning_interval(&mut self, interval: Duration) {
+        self.load_options.warn_limiter.set_interval(interval);
+    }
+
+    /// Check toggle names and file keys, as written, against `convention` when loading or
+    /// validating a file, reporting the violations.
+    pub fn set_naming_convention(&mut self, convention: NamingConvention) {
+        self.load_options.naming_convention = convention;
+    }
+
+    /// List the toggles whose value in the yaml file differs from the current one,
+    /// as `(toggle, current, file_value)`. A toggle whose rollout percentage would change is
+    /// listed even when its value stays the same.
+    pub fn diff_with_file(&self, filepath: &str) -> Result<Vec<ToggleChange<T>>, ToggleError> {
+        let document = self.read_with_local_override(filepath)?;
+        let (entries, _) = Self::expand_wildcards(document.entries);
+        let mut changes = Vec::new();
+        for (toggle_id, toggle) in T::iter().enumerate() {
+            let file_state = entries
+                .iter()
+                .rev()
+                .find(|(name, _)| name == toggle.as_ref())
+                .map(|(_, setting)| target(*setting));
+            if let Some((file_value, file_rollout)) = file_state {
+                let current = self.get(toggle_id);
+                if (current, self.rollouts[toggle_id]) != (file_value, file_rollout) {
+                    changes.push((toggle, current, file_value));
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Merge the file `filename`, e.g. `toggles.local.yaml`, on top of every loaded file when
+    /// it exists in the same directory, so developers can flip toggles locally without
+    /// touching the shared file. Disabled by default, `None` disables it again.
+    pub fn set_local_override(&mut self, filename: Option<&str>) {
+        self.load_options.local_override = filename.map(str::to_string);
+    }
+
+    /// Replace the wildcard keys (`Experiment*: 0`) by one entry per matching toggle, in
+    /// place so later keys still override them. Also returns the number of toggles matched
+    /// by each wildcard, the ones matching nothing are kept to be reported as unknown.
+    fn expand_wildcards(entries: Vec<(String, Setting)>) -> Expanded {
+        let mut expanded = Vec::with_capacity(entries.len());
+        let mut wildcards = Vec::new();
+        for (key, value) in entries {
+            if !naming::is_glob(&key) {
+                expanded.push((key, value));
+                continue;
+            }
+            let before = expanded.len();
+            for toggle in T::iter() {
+                if naming::glob_match(&key, toggle.as_ref()) {
+                    expanded.push((toggle.as_ref().to_string(), value));
+                }
+            }
+            let count = expanded.len() - before;
+            if count == 0 {
+                expanded.push((key.clone(), value));
+            }
+            wildcards.push((key, count));
+        }
+        (expanded, wildcards)
+    }
+
+    /// Read the toggles defined in the yaml file followed by the ones of the local override.
+    fn read_with_local_override(&self, filepath: &str) -> Result<Document, ToggleError> {
+        let mut document = self.read_file(filepath)?;
+        if let Some(filename) = &self.load_options.local_override {
+            let local_path = Path::new(filepath).with_file_name(filename);
+            if local_path.is_file() {
+                let local_path = local_path
+                    .to_str()
+                    .ok_or_else(|| ToggleError::Load("Invalid local override path".to_string()))?;
+                document.append(self.read_file(local_path)?);
+            }
+        }
+        Ok(document)
+    }
+
+    /// Read the toggles defined in the file, in file order, preceded by the ones of the files
+    /// it includes. The format is detected by `SourceFormat::detect`.
+    fn read_file(&self, filepath: &str) -> Result<Document, ToggleError> {
+        self.read_with_includes(Path::new(filepath), &mut Vec::new())
+    }
+
+    /// Read a file and, first, the files listed by its `include:` key relative to its
+    /// directory, `chain` holding the files being read to detect include loops.
+    fn read_with_includes(
+        &self,
+        path: &Path,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<Document, ToggleError> {
+        let canonical = fs::canonicalize(path)?;
+        if chain.contains(&canonical) {
+            return Err(ToggleError::Load(format!(
+                "Include loop on {}",
+                path.display()
+            )));
+        }
+        let content = self.read_content(path)?;
+        let format = SourceFormat::detect(path, &content);
+        let mut document = self.parse_document(&content, &format)?;
+        document.format = format;
+        if document.includes.is_empty() {
+            return Ok(document);
+        }
+        chain.push(canonical);
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let mut merged = Document::default();
+        for include in &document.includes {
+            merged.append(self.read_with_includes(&directory.join(include), chain)?);
+        }
+        chain.pop();
+        merged.format = document.format.clone();
+        merged.append(document);
+        Ok(merged)
+    }
+
+    /// Read a file of any format within the size limit, up to one byte past it so oversized
+    /// files are detected without being read in full.
+    pub(crate) fn read_content(&self, filepath: impl AsRef<Path>) -> Result<String, ToggleError> {
+        let content = self.read_bounded(fs::File::open(filepath)?)?;
+        self.load_options.parse_limits.check_size(&content)?;
+        Ok(content)
+    }
+
+    /// Read a document up to one byte past the size limit.
+    fn read_bounded(&self, reader: impl Read) -> Result<String, ToggleError> {
+        self.load_options.parse_limits.read(reader)
+    }
+
+    /// Parse a document of any format within the limits, with its keys resolved to the
+    /// toggle names.
+    pub(crate) fn parse_document(
+        &self,
+        content: &str,
+        format: &SourceFormat,
+    ) -> Result<Document, ToggleError> {
+        let mut document = Document::parse(content, format, &self.load_options.parse_limits)?;
+        if let SourceFormat::Dotenv(_) = format {
+            let names: Vec<(String, T)> = T::iter()
+                .map(|t| (naming::to_screaming_snake_case(t.as_ref()), t))
+                .collect();
+            for (key, _) in document.entries.iter_mut() {
+                if let Some((_, toggle)) = names.iter().find(|(name, _)| name == key) {
+                    *key = toggle.as_ref().to_string();
+                }
+            }
+        }
+        let mut keys: Vec<&str> = Vec::new();
+        for (key, _) in &document.entries {
+            if !naming::is_glob(key) && !keys.contains(&key.as_str()) {
+                keys.push(key);
+            }
+        }
+        document.naming_violations = keys
+            .into_iter()
+            .flat_map(|key| self.load_options.naming_convention.check(key))
+            .collect();
+        self.resolve_keys(&mut document);
+        document.duplicate_keys = Self::duplicate_keys(&document);
+        Ok(document)
+    }
+
+    /// Naming convention violations of the toggle names followed by `key_violations`, the ones
+    /// of the file keys.
+    fn naming_violations(&self, key_violations: &[String]) -> Vec<String> {
+        let mut violations: Vec<String> = T::iter()
+            .flat_map(|t| self.load_options.naming_convention.check(t.as_ref()))
+            .collect();
+        for violation in key_violations {
+            if !violations.contains(violation) {
+                violations.push(violation.clone());
+            }
+        }
+        violations
+    }
+
+    /// Toggle names set more than once by a document with resolved keys, `FeatureA` and its
+    /// alias, or a key repeated in a namespace.
+    fn duplicate_keys(document: &Document) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        let keys = document
+            .entries
+            .iter()
+            .map(|(key, _)| key)
+            .chain(&document.shadowed_keys);
+        for key in keys {
+            if !seen.insert(key) && !duplicates.contains(key) {
+                duplicates.push(key.clone());
+            }
+        }
+        duplicates
+    }
+
+    /// Set all toggles value defined in a file of the given format.
+    fn load_file_as(
+        &mut self,
+        filepath: &str,
+        format: &SourceFormat,
+    ) -> Result<LoadReport, ToggleError> {
+        let document = self.parse_document(&self.read_content(filepath)?, format)?;
+        self.set_source(filepath, format, Loader::Format);
+        Ok(self.apply_document(document, Provenance::File(filepath.to_string()), filepath))
+    }
+}
+
+/// Value and rollout buckets a file setting gives its toggle.
+fn target(setting: Setting) -> (bool, Option<u16>) {
+    match setting {
+        Setting::Value(value) => (value, None),
+        Setting::Rollout(percentage) => {
+            let buckets = rollout::buckets(percentage);
+            (rollout::rollout_value(buckets), Some(buckets))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq, Debug)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_load_from_file() {
+        // Create a temporary file
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+
+        // Write some data to the file
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle2: 0").expect("Unable to write to temporary file");
+        writeln!(temp_file, "VAR1: 0").expect("Unable to write to temporary file");
+        writeln!(temp_file).expect("Unable to write to temporary file");
+
+        // Get the path of the temporary file
+        let filepath = temp_file.path().to_str().unwrap();
+
+        // Create a Toggles instance and load from the file
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_file(filepath).unwrap();
+
+        // Verify that the toggles were set correctly
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(report.applied, vec!["Toggle1", "Toggle2"]);
+        assert_eq!(report.unknown_keys, vec!["VAR1"]);
+        assert!(report.missing_variants.is_empty());
+    }
+
+    #[test]
+    fn test_validate_file() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "VAR1: 0").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.validate_file(filepath).unwrap();
+        assert_eq!(report.applied, vec!["Toggle1"]);
+        assert_eq!(report.unknown_keys, vec!["VAR1"]);
+        assert_eq!(report.missing_variants, vec!["Toggle2"]);
+        assert!(report.naming_violations.is_empty());
+        assert!(!report.is_clean());
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+
+        toggles.set_naming_convention(NamingConvention::new().pascal_case());
+        let report = toggles.validate_file(filepath).unwrap();
+        assert_eq!(report.naming_violations, vec!["VAR1 is not PascalCase"]);
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert_eq!(report.naming_violations, vec!["VAR1 is not PascalCase"]);
+
+        let mut loose_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(loose_file, "toggle_1: 1").expect("Unable to write to temporary file");
+        toggles.set_loose_matching(true);
+        let report = toggles
+            .load_from_file(loose_file.path().to_str().unwrap())
+            .unwrap();
+        assert_eq!(report.applied, vec!["Toggle1"]);
+        assert_eq!(report.naming_violations, vec!["toggle_1 is not PascalCase"]);
+        toggles.set_loose_matching(false);
+
+        writeln!(temp_file, "Toggle1: 0").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        let report = toggles.validate_file(filepath).unwrap();
+        assert_eq!(report.duplicate_keys, vec!["Toggle1"]);
+
+        writeln!(temp_file, "Toggle2: 1O").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        assert!(toggles.validate_file(filepath).is_err());
+    }
+
+    #[test]
+    fn test_diff_with_file() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle2: 0").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let diff = toggles.diff_with_file(filepath).unwrap();
+        assert_eq!(diff.len(), 1);
+        assert!(diff[0].0 == TestToggles::Toggle1);
+        assert_eq!((diff[0].1, diff[0].2), (false, true));
+
+        fs::write(filepath, "Toggle1: 0\nToggle2: 37%\n").expect("Unable to write file");
+        let diff = toggles.diff_with_file(filepath).unwrap();
+        assert_eq!(diff.len(), 1);
+        assert!(diff[0].0 == TestToggles::Toggle2);
+        assert_eq!((diff[0].1, diff[0].2), (false, false));
+    }
+
+    #[test]
+    fn test_load_from_file_with_local_override() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        fs::write(&filepath, "Toggle1: 1\nToggle2: 0\n").expect("Unable to write file");
+        let filepath = filepath.to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_local_override(Some("toggles.local.yaml"));
+        toggles.load_from_file(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+
+        fs::write(dir.path().join("toggles.local.yaml"), "Toggle2: 1\n")
+            .expect("Unable to write file");
+        toggles.load_from_file(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+
+        fs::write(dir.path().join("toggles.local.yaml"), "Toggle3: 1\n")
+            .expect("Unable to write file");
+        assert_eq!(
+            toggles.validate_file(filepath).unwrap().unknown_keys,
+            vec!["Toggle3"]
+        );
+
+        toggles.set_local_override(None);
+        toggles.load_from_file(filepath).unwrap();
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+    }
+
+    #[test]
+    fn test_revert_last_load() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert!(!toggles.revert_last_load());
+
+        toggles.load_from_file(filepath).unwrap();
+        assert_eq!(
+            toggles.status()[0].provenance,
+            Provenance::File(filepath.to_string())
+        );
+        toggles.set(TestToggles::Toggle2 as usize, false);
+        assert!(toggles.revert_last_load());
+        assert_eq!(toggles.status()[0].provenance, Provenance::Reverted);
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert!(!toggles.revert_last_load());
+    }
+
+    #[test]
+    fn test_load_from_env_blob() {
+        env::set_var("ENUM_TOGGLES_TEST_BLOB", r#"{"Toggle2": 1, "VAR1": 0}"#);
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles
+            .load_from_env_blob("ENUM_TOGGLES_TEST_BLOB")
+            .unwrap();
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(
+            toggles.status()[1].provenance,
+            Provenance::Env("ENUM_TOGGLES_TEST_BLOB".to_string())
+        );
+
+        assert!(toggles
+            .load_from_env_blob("ENUM_TOGGLES_TEST_UNDEFINED")
+            .is_err());
+    }
+
+    #[test]
+    fn test_update_file_round_trip() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "# Kill switches").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle2: 0 # keep off").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.load_from_file(filepath).unwrap();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        toggles.update_file(filepath).unwrap();
+        assert_eq!(
+            fs::read_to_string(filepath).unwrap(),
+            "# Kill switches\nToggle2: 1 # keep off\nToggle1: 0\n"
+        );
+
+        let mut reloaded: EnumToggles<TestToggles> = EnumToggles::new();
+        reloaded.load_from_file(filepath).unwrap();
+        assert!(reloaded.get(TestToggles::Toggle2 as usize));
+
+        toggles.set_rollout(TestToggles::Toggle1, 37.0);
+        toggles.update_file(filepath).unwrap();
+        assert_eq!(
+            fs::read_to_string(filepath).unwrap(),
+            "# Kill switches\nToggle2: 1 # keep off\nToggle1: 37%\n"
+        );
+    }
+
+    #[test]
+    fn test_save_to_file_round_trip() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.set_rollout(TestToggles::Toggle2, 37.0);
+        toggles.save_to_file(&filepath).unwrap();
+
+        let mut reloaded: EnumToggles<TestToggles> = EnumToggles::new();
+        reloaded.load_from_file(filepath.to_str().unwrap()).unwrap();
+        assert_eq!(reloaded, toggles);
+        assert_eq!(
+            reloaded.rollout_percentage(TestToggles::Toggle2),
+            Some(37.0)
+        );
+    }
+
+    #[test]
+    fn test_save_in_loaded_format() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert!(matches!(toggles.save(), Err(ToggleError::Load(_))));
+
+        let filepath = dir.path().join("toggles.json");
+        fs::write(&filepath, "{\"Toggle1\": true}").expect("Unable to write file");
+        toggles.load_from_file(filepath.to_str().unwrap()).unwrap();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        toggles.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "{\n  \"Toggle1\": true,\n  \"Toggle2\": true\n}\n"
+        );
+
+        let filepath = dir.path().join("app.env");
+        fs::write(&filepath, "APP_TOGGLE1=0\n").expect("Unable to write file");
+        toggles
+            .load_from_dotenv(filepath.to_str().unwrap(), "APP_")
+            .unwrap();
+        toggles.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "APP_TOGGLE1=0\nAPP_TOGGLE2=1\n"
+        );
+    }
+
+    #[test]
+    fn test_save_keeps_rollouts() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let filepath = dir.path().join("toggles.yaml");
+        fs::write(&filepath, "Toggle1: 37%\n").expect("Unable to write file");
+        toggles.load_from_file(filepath.to_str().unwrap()).unwrap();
+        toggles.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "Toggle1: 37%\nToggle2: 0\n"
+        );
+
+        let filepath = dir.path().join("toggles.json");
+        fs::write(&filepath, "{}").expect("Unable to write file");
+        toggles.load_from_file(filepath.to_str().unwrap()).unwrap();
+        toggles.set_rollout(TestToggles::Toggle2, 12.5);
+        toggles.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "{\n  \"Toggle1\": \"37%\",\n  \"Toggle2\": \"12.5%\"\n}\n"
+        );
+        let mut reloaded: EnumToggles<TestToggles> = EnumToggles::new();
+        reloaded.load_from_file(filepath.to_str().unwrap()).unwrap();
+        assert_eq!(
+            reloaded.rollout_percentage(TestToggles::Toggle2),
+            Some(12.5)
+        );
+
+        let filepath = dir.path().join("toggles.properties");
+        fs::write(&filepath, "Toggle2=0\n").expect("Unable to write file");
+        toggles.load_from_file(filepath.to_str().unwrap()).unwrap();
+        assert!(matches!(toggles.save(), Err(ToggleError::Serialization(_))));
+        assert_eq!(fs::read_to_string(&filepath).unwrap(), "Toggle2=0\n");
+    }
+
+    #[test]
+    fn test_load_from_file_with_wildcards() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "\"Toggle*\": 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle2: 0").expect("Unable to write to temporary file");
+        writeln!(temp_file, "\"Other*\": 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.load_from_file(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+
+        let report = toggles.validate_file(filepath).unwrap();
+        assert_eq!(
+            report.wildcard_matches,
+            vec![("Toggle*".to_string(), 2), ("Other*".to_string(), 0)]
+        );
+        assert_eq!(report.unknown_keys, vec!["Other*"]);
+        assert!(report.missing_variants.is_empty());
+    }
+
+    #[test]
+    fn test_load_from_file_errors() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert!(matches!(
+            toggles.load_from_file("/nonexistent/toggles.yaml"),
+            Err(ToggleError::Io(_))
+        ));
+
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: maybe").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        assert_eq!(
+            toggles.load_from_file(filepath),
+            Err(ToggleError::TypeMismatch {
+                key: "value of Toggle1".to_string(),
+                expected: "0, 1, true, false, yes, no, on or off".to_string()
+            })
+        );
+
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: [1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        assert!(matches!(
+            toggles.load_from_file(filepath),
+            Err(ToggleError::Yaml(_))
+        ));
+    }
+
+    #[test]
+    fn test_reload() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert!(matches!(toggles.reload(), Err(ToggleError::Load(_))));
+
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert_eq!(report.changed, vec![("Toggle1".to_string(), false, true)]);
+        assert!(toggles.reload().unwrap().is_empty());
+
+        fs::write(filepath, "Toggle1: 0\nToggle2: 1\n").expect("Unable to write file");
+        assert_eq!(
+            toggles.reload().unwrap(),
+            vec![
+                (TestToggles::Toggle1, true, false),
+                (TestToggles::Toggle2, false, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reload_with_loader() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "APP_TOGGLE1=1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.load_from_dotenv(filepath, "APP_").unwrap();
+        for _ in 0..20 {
+            toggles.load_from_str("", SourceFormat::Yaml).unwrap();
+        }
+        fs::write(filepath, "APP_TOGGLE1=0\n").expect("Unable to write file");
+        assert_eq!(
+            toggles.reload().unwrap(),
+            vec![(TestToggles::Toggle1, true, false)]
+        );
+
+        fs::write(filepath, "{Toggle2: true}").expect("Unable to write file");
+        toggles.load_from_json(filepath).unwrap();
+        fs::write(filepath, "{Toggle2: false}").expect("Unable to write file");
+        assert_eq!(
+            toggles.reload().unwrap(),
+            vec![(TestToggles::Toggle2, true, false)]
+        );
+
+        fs::write(filepath, "Toggle1: 1\n").expect("Unable to write file");
+        toggles.load_from_file_strict(filepath).unwrap();
+        fs::write(filepath, "Toggle1: 0\nToggle11: 1\n").expect("Unable to write file");
+        assert!(matches!(toggles.reload(), Err(ToggleError::UnknownKeys(_))));
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+    }
+
+    #[test]
+    fn test_load_from_file_with_loose_matching() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "toggle_1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "TOGGLE-2: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert_eq!(report.unknown_keys, vec!["toggle_1", "TOGGLE-2"]);
+
+        toggles.set_loose_matching(true);
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(report.applied, vec!["Toggle1", "Toggle2"]);
+        assert!(toggles.load_from_file_strict(filepath).is_ok());
+    }
+
+    #[test]
+    fn test_load_from_file_with_alias() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "OldToggle2: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.add_alias("OldToggle2", TestToggles::Toggle2);
+        let report = toggles.load_from_file_strict(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(report.applied, vec!["Toggle2"]);
+        assert!(toggles
+            .validate_file(filepath)
+            .unwrap()
+            .unknown_keys
+            .is_empty());
+    }
+
+    #[test]
+    fn test_load_from_file_with_deprecated_name() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "LegacyToggle1: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.add_deprecated_name("LegacyToggle1", TestToggles::Toggle1);
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(report.applied, vec!["Toggle1"]);
+        assert_eq!(
+            report.deprecated_keys,
+            vec![("LegacyToggle1".to_string(), "Toggle1".to_string())]
+        );
+        assert!(!toggles.validate_file(filepath).unwrap().is_clean());
+    }
+
+    #[test]
+    fn test_load_from_json() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(
+            temp_file,
+            r#"{{"Toggle1": true, "Toggle2": 0, "VAR1": false}}"#
+        )
+        .expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_json(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(report.unknown_keys, vec!["VAR1"]);
+
+        fs::write(
+            filepath,
+            "{\n  // Back to the old flow\n  Toggle1: false,\n  /* ready */ 'Toggle2': 1,\n}\n",
+        )
+        .expect("Unable to write file");
+        toggles.load_from_json(filepath).unwrap();
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_load_from_toml() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(
+            temp_file,
+            "name = \"app\"\n\n[toggles]\nToggle1 = true\nVAR1 = false"
+        )
+        .expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_toml(filepath, Some("toggles")).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(report.applied, vec!["Toggle1"]);
+        assert_eq!(report.unknown_keys, vec!["VAR1"]);
+
+        toggles.set_parse_limits(ParseLimits::new().max_keys(1));
+        assert!(matches!(
+            toggles.load_from_toml(filepath, Some("toggles")),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_from_dotenv() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(
+            temp_file,
+            "PORT=8080\nAPP_TOGGLE1=1\nAPP_Toggle2=on\nAPP_VAR1=0"
+        )
+        .expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_dotenv(filepath, "APP_").unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(report.applied, vec!["Toggle1", "Toggle2"]);
+        assert_eq!(report.unknown_keys, vec!["VAR1"]);
+    }
+
+    #[test]
+    fn test_load_from_ini() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1 = 1\n[Toggle]\n2 = yes\n3 = no")
+            .expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_ini(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(report.unknown_keys, vec!["Toggle3"]);
+    }
+
+    #[test]
+    fn test_load_from_properties() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(
+            temp_file,
+            "# shared with the JVM services\nToggle1=1\nToggle2: false"
+        )
+        .expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_properties(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(report.applied, vec!["Toggle1", "Toggle2"]);
+    }
+
+    #[test]
+    #[cfg(feature = "ron")]
+    fn test_load_from_ron() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "(Toggle1: true, VAR1: false)")
+            .expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_ron(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(report.applied, vec!["Toggle1"]);
+        assert_eq!(report.unknown_keys, vec!["VAR1"]);
+    }
+
+    #[test]
+    fn test_load_from_file_with_namespaces() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "toggle:\n  \"1\": 1\n  \"2\": 0")
+            .expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(report.applied, vec!["Toggle1", "Toggle2"]);
+    }
+
+    #[test]
+    fn test_load_from_file_with_rollouts() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 37%\nToggle2: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert_eq!(report.applied, vec!["Toggle1", "Toggle2"]);
+        assert!(report.missing_variants.is_empty());
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), Some(37.0));
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.validate_file(filepath).unwrap().is_complete());
+
+        fs::write(filepath, "Toggle1: 1\n").expect("Unable to write file");
+        toggles.load_from_file(filepath).unwrap();
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), None);
+        assert!(toggles.rollout(TestToggles::Toggle1, "user-1"));
+        assert!(toggles.revert_last_load());
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), Some(37.0));
+
+        toggles.trip(TestToggles::Toggle2, "errors", Duration::ZERO);
+        fs::write(filepath, "Toggle2: 100%\n").expect("Unable to write file");
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert_eq!(report.applied, vec!["Toggle2"]);
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(toggles.expire_trips(), vec![TestToggles::Toggle2]);
+        assert_eq!(
+            toggles.rollout_percentage(TestToggles::Toggle2),
+            Some(100.0)
+        );
+
+        toggles.mark_startup_only(TestToggles::Toggle1);
+        toggles.finish_startup();
+        fs::write(filepath, "Toggle1: 50%\n").expect("Unable to write file");
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), Some(37.0));
+
+        fs::write(filepath, "Toggle3: 5%\nToggle1: 0\nToggle2: 0\n").expect("Unable to write file");
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert_eq!(report.applied, vec!["Toggle2"]);
+        assert_eq!(report.unknown_keys, vec!["Toggle3"]);
+        assert!(report.missing_variants.is_empty());
+    }
+
+    #[test]
+    fn test_load_from_file_with_includes() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        fs::create_dir(dir.path().join("shared")).expect("Unable to create directory");
+        fs::write(
+            dir.path().join("shared/base.yaml"),
+            "Toggle1: 1\nToggle2: 1\n",
+        )
+        .expect("Unable to write file");
+        let filepath = dir.path().join("service.yaml");
+        fs::write(&filepath, "include: shared/base.yaml\nToggle2: 0\n")
+            .expect("Unable to write file");
+        let filepath = filepath.to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.load_from_file(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+
+        fs::write(
+            dir.path().join("shared/base.yaml"),
+            "include: ../service.yaml\n",
+        )
+        .expect("Unable to write file");
+        assert!(matches!(
+            toggles.load_from_file(filepath),
+            Err(ToggleError::Load(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_from_file_detects_format() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+
+        let filepath = dir.path().join("toggles.properties");
+        fs::write(&filepath, "Toggle1=1\n").expect("Unable to write file");
+        toggles.load_from_file(filepath.to_str().unwrap()).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+
+        let filepath = dir.path().join("toggles");
+        fs::write(&filepath, "{\"Toggle1\": false, // sniffed\n}").expect("Unable to write file");
+        toggles.load_from_file(filepath.to_str().unwrap()).unwrap();
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+    }
+
+    #[test]
+    fn test_load_from_str() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles
+            .load_from_str("Toggle1: 1\nVAR1: 0\n", SourceFormat::Yaml)
+            .unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(report.unknown_keys, vec!["VAR1"]);
+        assert_eq!(
+            toggles.provenance[TestToggles::Toggle1 as usize],
+            Provenance::Api
+        );
+
+        toggles
+            .load_from_str("APP_TOGGLE2=1\n", SourceFormat::Dotenv("APP_".to_string()))
+            .unwrap();
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+
+        toggles
+            .load_from_str("Toggle1=0\n", SourceFormat::Properties)
+            .unwrap();
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+
+        assert!(toggles
+            .load_from_str("Toggle1 = maybe\n", SourceFormat::Ini)
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_from_reader() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let report = toggles
+            .load_from_reader(&b"{\"Toggle2\": true}"[..], SourceFormat::Json)
+            .unwrap();
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(report.applied, vec!["Toggle2"]);
+
+        toggles.set_parse_limits(ParseLimits::new().max_file_size(8));
+        assert!(matches!(
+            toggles.load_from_reader(std::io::repeat(b' '), SourceFormat::Yaml),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_from_file_is_atomic() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        fs::write(&filepath, "Toggle1: 1\nToggle2: 0\n").expect("Unable to write file");
+        let filepath = filepath.to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.load_from_file(filepath).unwrap();
+
+        fs::write(filepath, "Toggle1: 0\nToggle2: maybe\n").expect("Unable to write file");
+        assert!(toggles.load_from_file(filepath).is_err());
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(toggles.generation(), 1);
+
+        fs::write(filepath, "Toggle1: 0\nToggle2: 1\n").expect("Unable to write file");
+        fs::write(dir.path().join("toggles.local.yaml"), "Toggle1: [1\n")
+            .expect("Unable to write file");
+        toggles.set_local_override(Some("toggles.local.yaml"));
+        assert!(toggles.load_from_file(filepath).is_err());
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(toggles.generation(), 1);
+    }
+
+    #[test]
+    fn test_load_from_file_strict() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle11: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert_eq!(
+            toggles.load_from_file_strict(filepath),
+            Err(ToggleError::UnknownKeys(vec!["Toggle11".to_string()]))
+        );
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(toggles.generation(), 0);
+
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        toggles.load_from_file_strict(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+    }
+
+    #[test]
+    fn test_validate_file_complete() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle2: 0").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        let toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert_eq!(toggles.validate_file_complete(filepath), Ok(()));
+
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle1: 0").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        assert_eq!(
+            toggles.validate_file_complete(filepath),
+            Err(ToggleError::Incomplete {
+                missing_variants: vec!["Toggle2".to_string()],
+                duplicate_keys: vec!["Toggle1".to_string()],
+                unknown_keys: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_from_file_duplicates() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle1: 0").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert_eq!(
+            toggles.load_from_file_strict(filepath),
+            Err(ToggleError::DuplicateKeys(vec!["Toggle1".to_string()]))
+        );
+        let report = toggles.load_from_file(filepath).unwrap();
+        assert_eq!(report.duplicate_keys, vec!["Toggle1"]);
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+
+        let dir = tempfile::tempdir().unwrap();
+        let filepath = dir.path().join("toggles.properties");
+        fs::write(&filepath, "Toggle2=1\nToggle2=0\n").expect("Unable to write file");
+        let filepath = filepath.to_str().unwrap();
+        assert_eq!(
+            toggles.load_from_file_strict(filepath),
+            Err(ToggleError::DuplicateKeys(vec!["Toggle2".to_string()]))
+        );
+
+        let filepath = dir.path().join("toggles.yaml");
+        fs::write(&filepath, "toggle:\n  \"2\": 1\n  \"2\": 0\n").expect("Unable to write file");
+        let filepath = filepath.to_str().unwrap();
+        assert_eq!(
+            toggles.validate_file(filepath).unwrap().duplicate_keys,
+            vec!["Toggle2"]
+        );
+
+        fs::write(filepath, "Toggle1: 1\nOldToggle1: 0\n").expect("Unable to write file");
+        toggles.add_alias("OldToggle1", TestToggles::Toggle1);
+        assert_eq!(
+            toggles.load_from_file_strict(filepath),
+            Err(ToggleError::DuplicateKeys(vec!["Toggle1".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Toggle2: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_parse_limits(ParseLimits::new().max_file_size(12));
+        assert!(matches!(
+            toggles.load_from_file(filepath),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+        toggles.set_parse_limits(ParseLimits::new().max_keys(1));
+        assert!(matches!(
+            toggles.load_from_file(filepath),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+        toggles.set_parse_limits(ParseLimits::new());
+        toggles.load_from_file(filepath).unwrap();
+        assert!(toggles.all());
+
+        let empty = tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        let report = toggles
+            .load_from_file(empty.path().to_str().unwrap())
+            .unwrap();
+        assert!(report.applied.is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::ReadToggles;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+/// Programmable toggles for unit tests of code written against [`ReadToggles`].
+///
+/// Values are scripted per toggle and every read is recorded, so no file or global is needed.
+pub struct MockToggles<T> {
+    state: Mutex<MockState>,
+    _marker: PhantomData<T>,
+}
+
+struct MockState {
+    values: Vec<bool>,
+    scripts: Vec<VecDeque<bool>>,
+    reads: Vec<usize>,
+}
+
+impl<T> Default for MockToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MockToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Create a new instance of `MockToggles` with all toggles set to false.
+    pub fn new() -> Self {
+        let count = T::iter().count();
+        MockToggles {
+            state: Mutex::new(MockState {
+                values: vec![false; count],
+                scripts: vec![VecDeque::new(); count],
+                reads: vec![0; count],
+            }),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the value returned by every read of a toggle, chainable.
+    pub fn with(self, toggle: T, value: bool) -> Self {
+        self.set(toggle, value);
+        self
+    }
+
+    /// Set the value returned by every read of a toggle.
+    pub fn set(&self, toggle: T, value: bool) {
+        let toggle_id = Self::toggle_id(&toggle);
+        let mut state = self.state.lock().unwrap();
+        state.values[toggle_id] = value;
+        state.scripts[toggle_id].clear();
+    }
+
+    /// Return `values` on the next reads of a toggle, in order. Once exhausted, reads keep
+    /// returning the last scripted value.
+    pub fn script(&self, toggle: T, values: impl IntoIterator<Item = bool>) {
+        let toggle_id = Self::toggle_id(&toggle);
+        let mut state = self.state.lock().unwrap();
+        state.scripts[toggle_id] = values.into_iter().collect();
+    }
+
+    /// Number of times a toggle has been read.
+    pub fn read_count(&self, toggle: T) -> usize {
+        self.reads(Self::toggle_id(&toggle))
+    }
+
+    /// Panic if a toggle has never been read.
+    pub fn assert_read(&self, toggle: T) {
+        if self.reads(Self::toggle_id(&toggle)) == 0 {
+            panic!("Toggle {} was never read", toggle.as_ref());
+        }
+    }
+
+    /// Panic if a toggle has been read.
+    pub fn assert_not_read(&self, toggle: T) {
+        let count = self.reads(Self::toggle_id(&toggle));
+        if count != 0 {
+            panic!("Toggle {} was read {} times", toggle.as_ref(), count);
+        }
+    }
+
+    fn toggle_id(toggle: &T) -> usize {
+        T::iter()
+            .position(|x| &x == toggle)
+            .expect("Every variant is yielded by its own iterator")
+    }
+
+    fn reads(&self, toggle_id: usize) -> usize {
+        self.state.lock().unwrap().reads[toggle_id]
+    }
+}
+
+impl<T> ReadToggles<T> for MockToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn is_enabled(&self, toggle: T) -> bool {
+        let toggle_id = Self::toggle_id(&toggle);
+        let mut state = self.state.lock().unwrap();
+        state.reads[toggle_id] += 1;
+        if let Some(value) = state.scripts[toggle_id].pop_front() {
+            state.values[toggle_id] = value;
+        }
+        state.values[toggle_id]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    fn code_under_test(toggles: &impl ReadToggles<TestToggles>) -> &'static str {
+        if toggles.is_enabled(TestToggles::Toggle1) {
+            "new"
+        } else {
+            "old"
+        }
+    }
+
+    #[test]
+    fn test_scripted_values() {
+        let toggles: MockToggles<TestToggles> = MockToggles::new();
+        toggles.script(TestToggles::Toggle1, [true, false]);
+        assert_eq!(code_under_test(&toggles), "new");
+        assert_eq!(code_under_test(&toggles), "old");
+        assert_eq!(code_under_test(&toggles), "old");
+        assert_eq!(toggles.read_count(TestToggles::Toggle1), 3);
+        toggles.assert_read(TestToggles::Toggle1);
+        toggles.assert_not_read(TestToggles::Toggle2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Toggle Toggle2 was never read")]
+    fn test_assert_read() {
+        let toggles = MockToggles::new().with(TestToggles::Toggle1, true);
+        assert_eq!(code_under_test(&toggles), "new");
+        toggles.assert_read(TestToggles::Toggle2);
+    }
+}
@@ -0,0 +1,113 @@
+/// Convert a `PascalCase` variant name into `SCREAMING_SNAKE_CASE` (`HttpServer` -> `HTTP_SERVER`).
+pub(crate) fn to_screaming_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                result.push('_');
+            }
+        }
+        result.extend(c.to_uppercase());
+    }
+    result
+}
+
+/// Uppercase the first letter of a namespaced name, `payments` prefixing `PaymentsNewCheckout`.
+pub(crate) fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Read a toggle value written as text: `true`/`false`, `yes`/`no`, `on`/`off`, `1`/`0` in
+/// any case.
+pub(crate) fn str_to_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Fold a name for loose matching, so `feature_a`, `feature-a`, `FEATURE_A` and `FeatureA`
+/// compare equal.
+#[cfg(feature = "yaml")]
+pub(crate) fn fold(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// True if `pattern` contains a `*` or `?` wildcard.
+#[cfg(feature = "yaml")]
+pub(crate) fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Match a name against a glob pattern where `*` matches any run of characters and `?`
+/// exactly one.
+#[cfg(feature = "yaml")]
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_screaming_snake_case() {
+        assert_eq!(to_screaming_snake_case("FeatureA"), "FEATURE_A");
+        assert_eq!(to_screaming_snake_case("HTTPServer"), "HTTP_SERVER");
+        assert_eq!(to_screaming_snake_case("Toggle1"), "TOGGLE1");
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_fold() {
+        for name in ["feature_a", "feature-a", "FEATURE_A", "FeatureA"] {
+            assert_eq!(fold(name), "featurea");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_glob_match() {
+        assert!(glob_match("Experiment*", "ExperimentNewSearch"));
+        assert!(glob_match("Experiment*", "Experiment"));
+        assert!(glob_match("*Search", "ExperimentNewSearch"));
+        assert!(glob_match("Feature?", "FeatureA"));
+        assert!(!glob_match("Feature?", "FeatureAB"));
+        assert!(!glob_match("Experiment*", "NewExperiment"));
+        assert!(is_glob("Experiment*"));
+        assert!(!is_glob("FeatureA"));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{EnumToggles, Provenance};
+use bitvec::prelude::*;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+/// Results of the set operators.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    fn from_values(values: BitVec) -> Self {
+        let mut toggles = EnumToggles::new();
+        toggles.replace_values(&values, Provenance::Api);
+        toggles
+    }
+}
+
+macro_rules! impl_set_operator {
+    ($($trait:ident, $method:ident;)*) => {
+        $(
+            /// Combine the values of two toggle sets of the same enum, word by word.
+            impl<T> $trait<&EnumToggles<T>> for &EnumToggles<T>
+            where
+                T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+            {
+                type Output = EnumToggles<T>;
+
+                fn $method(self, other: &EnumToggles<T>) -> EnumToggles<T> {
+                    EnumToggles::from_values(
+                        self.toggles_value.clone().$method(&other.toggles_value),
+                    )
+                }
+            }
+
+            impl<T> $trait for EnumToggles<T>
+            where
+                T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+            {
+                type Output = EnumToggles<T>;
+
+                fn $method(self, other: EnumToggles<T>) -> EnumToggles<T> {
+                    (&self).$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+impl_set_operator! {
+    BitAnd, bitand;
+    BitOr, bitor;
+    BitXor, bitxor;
+}
+
+/// Invert the values of all toggles.
+impl<T> Not for &EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    type Output = EnumToggles<T>;
+
+    fn not(self) -> EnumToggles<T> {
+        EnumToggles::from_values(!self.toggles_value.clone())
+    }
+}
+
+impl<T> Not for EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    type Output = EnumToggles<T>;
+
+    fn not(self) -> EnumToggles<T> {
+        !&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::EnumToggles;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+        Toggle3,
+    }
+
+    fn toggles(values: [bool; 3]) -> EnumToggles<TestToggles> {
+        let mut toggles = EnumToggles::new();
+        for (toggle_id, value) in values.into_iter().enumerate() {
+            toggles.set(toggle_id, value);
+        }
+        toggles
+    }
+
+    #[test]
+    fn test_set_operators() {
+        let allowed = toggles([true, true, false]);
+        let requested = toggles([false, true, true]);
+        assert_eq!(&allowed & &requested, toggles([false, true, false]));
+        assert_eq!(&allowed | &requested, toggles([true, true, true]));
+        assert_eq!(&allowed ^ &requested, toggles([true, false, true]));
+        assert_eq!(!&allowed, toggles([false, false, true]));
+        assert_eq!(allowed & requested, toggles([false, true, false]));
+    }
+}
@@ -0,0 +1,325 @@
+use crate::load::{Document, Loader};
+use crate::yaml::Setting;
+use crate::{EnumToggles, LoadReport, Provenance, SourceFormat, ToggleError};
+
+/// Resolve a profile of the top-level `environments:` map, following `inherits:` from the
+/// base profile to the requested one. Returns the sections to apply in order, each one a
+/// yaml document, so later profiles override the values of their parents. Files made of
+/// documents labelled `--- # name` are resolved by `resolve_labelled` instead.
+pub(crate) fn resolve(content: &str, profile: &str) -> Result<Vec<String>, ToggleError> {
+    let documents = split_documents(content);
+    if documents.iter().any(|(label, _)| label.is_some()) {
+        return resolve_labelled(documents, profile);
+    }
+    let sections = environment_sections(content).ok_or_else(|| ToggleError::TypeMismatch {
+        key: "environments".to_string(),
+        expected: "a top-level map".to_string(),
+    })?;
+
+    let mut chain: Vec<&Section> = Vec::new();
+    let mut current = profile;
+    loop {
+        if chain.iter().any(|section| section.name == current) {
+            let names: Vec<&str> = chain.iter().map(|section| section.name.as_str()).collect();
+            return Err(ToggleError::Load(format!(
+                "Profile inheritance loop: {} -> {}",
+                names.join(" -> "),
+                current
+            )));
+        }
+        let section = sections
+            .iter()
+            .find(|section| section.name == current)
+            .ok_or_else(|| ToggleError::Load(format!("Unknown profile {}", current)))?;
+        chain.push(section);
+        match &section.inherits {
+            Some(parent) => current = parent,
+            None => break,
+        }
+    }
+    Ok(chain
+        .into_iter()
+        .rev()
+        .map(|section| section.body.clone())
+        .collect())
+}
+
+/// A profile of the `environments:` map, its body dedented so it reads as a document of its
+/// own, without its `inherits:` key.
+struct Section {
+    name: String,
+    inherits: Option<String>,
+    body: String,
+}
+
+/// Split the top-level `environments:` block map into its sections, `None` if the file has
+/// no such map.
+fn environment_sections(content: &str) -> Option<Vec<Section>> {
+    let mut lines = content.lines();
+    lines.find(|line| {
+        key_value(line).is_some_and(|(key, value)| key == "environments" && value.is_empty())
+    })?;
+    let mut sections: Vec<Section> = Vec::new();
+    let mut section_indent = None;
+    let mut body_indent = None;
+    for line in lines {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if indent == 0 {
+            break;
+        }
+        if indent <= *section_indent.get_or_insert(indent) {
+            let (name, value) = key_value(trimmed)?;
+            if !value.is_empty() {
+                return None;
+            }
+            sections.push(Section {
+                name: name.to_string(),
+                inherits: None,
+                body: String::new(),
+            });
+            body_indent = None;
+            continue;
+        }
+        let section = sections.last_mut()?;
+        let body_line = line
+            .get(indent.min(*body_indent.get_or_insert(indent))..)
+            .unwrap_or(trimmed);
+        match key_value(body_line) {
+            Some(("inherits", parent)) => section.inherits = Some(parent.to_string()),
+            _ => {
+                section.body.push_str(body_line);
+                section.body.push('\n');
+            }
+        }
+    }
+    Some(sections)
+}
+
+/// Split a `key: value  # comment` line without indentation into its unquoted key and value.
+fn key_value(line: &str) -> Option<(&str, &str)> {
+    if line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let (key, value) = line.split_once(':')?;
+    let value = value.split(" #").next().unwrap_or_default().trim();
+    Some((
+        key.trim().trim_matches(|c| c == '"' || c == '\''),
+        value.trim_matches(|c| c == '"' || c == '\''),
+    ))
+}
+
+/// Split a multi-document file on its `---` lines, along with the label of each document,
+/// the comment of its `--- # name` line.
+fn split_documents(content: &str) -> Vec<(Option<&str>, String)> {
+    let mut documents: Vec<(Option<&str>, String)> = vec![(None, String::new())];
+    for line in content.lines() {
+        let separator = line
+            .strip_prefix("---")
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+        match separator {
+            Some(rest) => {
+                let label = rest.trim().strip_prefix('#').map(str::trim);
+                documents.push((label.filter(|l| !l.is_empty()), String::new()));
+            }
+            None => {
+                let (_, document) = documents.last_mut().expect("At least one document");
+                document.push_str(line);
+                document.push('\n');
+            }
+        }
+    }
+    documents
+}
+
+/// Resolve a profile of a file made of documents labelled `--- # name`, the unlabelled ones
+/// holding values shared by every profile and overridden by the labelled one.
+fn resolve_labelled(
+    documents: Vec<(Option<&str>, String)>,
+    profile: &str,
+) -> Result<Vec<String>, ToggleError> {
+    if !documents.iter().any(|(label, _)| *label == Some(profile)) {
+        return Err(ToggleError::Load(format!("Unknown profile {}", profile)));
+    }
+    Ok(documents
+        .into_iter()
+        .filter(|(label, _)| label.is_none() || *label == Some(profile))
+        .map(|(_, document)| document)
+        .collect())
+}
+
+/// Per-environment profiles.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Set all toggles value defined by a profile of the yaml file, e.g.
+    /// ```yaml
+    /// environments:
+    ///   base:
+    ///     FeatureA: 0
+    ///     FeatureB: 1
+    ///   prod:
+    ///     inherits: base
+    ///     FeatureA: 1
+    /// ```
+    /// or one document per profile, the unlabelled ones being shared by every profile:
+    /// ```yaml
+    /// FeatureB: 1
+    /// --- # dev
+    /// FeatureA: 1
+    /// --- # prod
+    /// FeatureA: 0
+    /// ```
+    /// Each section is read like a file of `load_from_file`, with the same reporting.
+    pub fn load_from_file_for_env(
+        &mut self,
+        filepath: &str,
+        profile: &str,
+    ) -> Result<LoadReport, ToggleError> {
+        let document = self.read_profile(filepath, profile)?;
+        self.set_source(
+            filepath,
+            &SourceFormat::Yaml,
+            Loader::Profile(profile.to_string()),
+        );
+        Ok(self.apply_document(document, Provenance::File(filepath.to_string()), filepath))
+    }
+
+    /// Resolved view of a profile once inheritance is applied, as `(name, value)` pairs with
+    /// the keys resolved to the toggle names. Rollout percentages are left out.
+    pub fn resolved_profile(
+        &self,
+        filepath: &str,
+        profile: &str,
+    ) -> Result<Vec<(String, bool)>, ToggleError> {
+        let mut resolved: Vec<(String, bool)> = Vec::new();
+        for (key, setting) in self.read_profile(filepath, profile)?.entries {
+            let Setting::Value(value) = setting else {
+                continue;
+            };
+            match resolved.iter_mut().find(|(name, _)| *name == key) {
+                Some(existing) => existing.1 = value,
+                None => resolved.push((key, value)),
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Read the sections of a profile, one after the other.
+    fn read_profile(&self, filepath: &str, profile: &str) -> Result<Document, ToggleError> {
+        let content = self.read_content(filepath)?;
+        let mut document = Document::default();
+        for section in resolve(&content, profile)? {
+            document.append(self.parse_document(&section, &SourceFormat::Yaml)?);
+        }
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq, Debug)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    const PROFILES: &str = "
+environments:
+  base:
+    Toggle1: 0
+    Toggle2: 1
+  staging:
+    inherits: base
+    Toggle1: 1
+  prod:
+    inherits: staging
+    Toggle2: 0
+  loop_a:
+    inherits: loop_b
+  loop_b:
+    inherits: loop_a
+";
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            resolve(PROFILES, "prod").unwrap(),
+            vec!["Toggle1: 0\nToggle2: 1\n", "Toggle1: 1\n", "Toggle2: 0\n"]
+        );
+        assert_eq!(
+            resolve(PROFILES, "base").unwrap(),
+            vec!["Toggle1: 0\nToggle2: 1\n"]
+        );
+        assert_eq!(
+            resolve(PROFILES, "loop_a").unwrap_err(),
+            ToggleError::Load("Profile inheritance loop: loop_a -> loop_b -> loop_a".to_string())
+        );
+        assert!(resolve(PROFILES, "dev").is_err());
+        assert!(matches!(
+            resolve("Toggle1: 1\n", "prod"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_labelled_documents() {
+        let content = "Toggle2: 1\n--- # dev\nToggle1: 1\n--- # prod\nToggle1: 0\nToggle2: 0\n";
+        assert_eq!(
+            resolve(content, "dev").unwrap(),
+            vec!["Toggle2: 1\n", "Toggle1: 1\n"]
+        );
+        assert_eq!(
+            resolve(content, "prod").unwrap(),
+            vec!["Toggle2: 1\n", "Toggle1: 0\nToggle2: 0\n"]
+        );
+        assert_eq!(
+            resolve(content, "staging").unwrap_err(),
+            ToggleError::Load("Unknown profile staging".to_string())
+        );
+        assert_eq!(split_documents("Toggle1: 1\n---- # dev\n").len(), 1);
+    }
+
+    #[test]
+    fn test_load_from_file_for_env() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        fs::write(
+            &filepath,
+            "environments:\n  base:\n    OldToggle1: 1\n    toggle:\n      \"2\": 0\n  prod:\n    \
+             inherits: base\n    Toggle2: 25%\n    Toggle2: 30%\n",
+        )
+        .expect("Unable to write file");
+        let filepath = filepath.to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.add_alias("OldToggle1", TestToggles::Toggle1);
+        let report = toggles.load_from_file_for_env(filepath, "prod").unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle2), Some(30.0));
+        assert_eq!(report.duplicate_keys, vec!["Toggle2"]);
+        assert_eq!(
+            toggles.resolved_profile(filepath, "base").unwrap(),
+            vec![
+                ("Toggle1".to_string(), true),
+                ("Toggle2".to_string(), false)
+            ]
+        );
+
+        fs::write(filepath, "environments:\n  prod:\n    Toggle1: 0\n")
+            .expect("Unable to write file");
+        assert_eq!(
+            toggles.reload().unwrap(),
+            vec![(TestToggles::Toggle1, true, false)]
+        );
+        assert!(matches!(toggles.save(), Err(ToggleError::Load(_))));
+    }
+}
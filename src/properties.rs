@@ -0,0 +1,53 @@
+use crate::{yaml, ToggleError};
+
+/// Parse the toggles defined in a Java `.properties` file, in file order. Keys and values are
+/// separated by `=`, `:` or whitespace, and lines starting with `#` or `!` are comments.
+pub(crate) fn parse(content: &str) -> Result<Vec<(String, bool)>, ToggleError> {
+    yaml::text_entries(parse_raw(content)?, "")
+}
+
+/// Parse the keys of a Java `.properties` file like `parse`, keeping the values as text.
+pub(crate) fn parse_raw(content: &str) -> Result<Vec<(String, String)>, ToggleError> {
+    let mut entries = Vec::new();
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let mut line = line.trim_start().to_string();
+        if line.is_empty() || line.starts_with(['#', '!']) {
+            continue;
+        }
+        while line.ends_with('\\') {
+            line.pop();
+            line.push_str(lines.next().unwrap_or_default().trim_start());
+        }
+        let split = line.find(['=', ':', ' ', '\t']).unwrap_or(line.len());
+        let (key, raw) = line.split_at(split);
+        let raw = raw.trim_start();
+        let raw = raw.strip_prefix(['=', ':']).unwrap_or(raw);
+        entries.push((key.to_string(), raw.trim().to_string()));
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "# flags\n! legacy\nFeatureA=1\nFeatureB : false\nFeatureC on\n\
+            FeatureD = \\\n    true\n";
+        assert_eq!(
+            parse(content).unwrap(),
+            vec![
+                ("FeatureA".to_string(), true),
+                ("FeatureB".to_string(), false),
+                ("FeatureC".to_string(), true),
+                ("FeatureD".to_string(), true)
+            ]
+        );
+        assert!(matches!(
+            parse("FeatureA=enabled\n"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+    }
+}
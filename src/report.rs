@@ -0,0 +1,64 @@
+/// Outcome of reading a toggle file against the enum.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Toggles declared in the file, in file order. Loads leave out the changes rejected by a
+    /// startup-only toggle.
+    pub applied: Vec<String>,
+    /// Keys of the file that don't match any toggle.
+    pub unknown_keys: Vec<String>,
+    /// Toggles not declared in the file, they keep their current value.
+    pub missing_variants: Vec<String>,
+    /// Toggle names and file keys breaking the naming convention.
+    pub naming_violations: Vec<String>,
+    /// Keys declared more than once in the file, the last value wins.
+    pub duplicate_keys: Vec<String>,
+    /// Wildcard keys (`Experiment*`) and the number of toggles they matched.
+    pub wildcard_matches: Vec<(String, usize)>,
+    /// Deprecated names used by the file, as `(deprecated name, toggle name)`.
+    pub deprecated_keys: Vec<(String, String)>,
+    /// Toggles whose value was changed by the load, as `(name, old value, new value)`.
+    pub changed: Vec<(String, bool, bool)>,
+}
+
+impl LoadReport {
+    /// Cross-check the keys of a file against the toggle names.
+    ///
+    /// This operation is *O*(*n* × *m*).
+    #[cfg(feature = "yaml")]
+    pub(crate) fn new<'a>(
+        names: impl Iterator<Item = &'a str>,
+        keys: impl Iterator<Item = &'a str> + Clone,
+    ) -> Self {
+        let names: Vec<&str> = names.collect();
+        let mut report = LoadReport::default();
+        for key in keys.clone() {
+            if names.contains(&key) {
+                report.applied.push(key.to_string());
+            } else {
+                report.unknown_keys.push(key.to_string());
+            }
+        }
+        for name in names {
+            if !keys.clone().any(|key| key == name) {
+                report.missing_variants.push(name.to_string());
+            }
+        }
+        report
+    }
+
+    /// True when the file declares every toggle exactly once and nothing else.
+    pub fn is_complete(&self) -> bool {
+        self.missing_variants.is_empty()
+            && self.duplicate_keys.is_empty()
+            && self.unknown_keys.is_empty()
+    }
+
+    /// True when every key of the file matches a toggle, follows the naming convention, is
+    /// declared once and isn't a deprecated name.
+    pub fn is_clean(&self) -> bool {
+        self.unknown_keys.is_empty()
+            && self.naming_violations.is_empty()
+            && self.duplicate_keys.is_empty()
+            && self.deprecated_keys.is_empty()
+    }
+}
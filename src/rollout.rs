@@ -0,0 +1,163 @@
+use crate::{hash, EnumToggles, Exposure, Provenance};
+use std::time::SystemTime;
+
+/// Number of buckets the context keys are spread over, a percentage being worth 100 of them.
+const BUCKETS: u64 = 10_000;
+
+/// Gradual rollouts, a toggle being enabled for a stable share of the context keys.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Enable `toggle` for `percentage` percent of the context keys, clamped to 0..=100.
+    /// The toggle reads as enabled with `get` only at 100 percent, setting its value again
+    /// ends the rollout.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn set_rollout(&mut self, toggle: T, percentage: f64) {
+        let toggle_id = self.toggle_id(&toggle);
+        self.set_rollout_by_id(toggle_id, buckets(percentage), Provenance::Api);
+    }
+
+    /// Percentage of the context keys `toggle` is enabled for, `None` without rollout.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn rollout_percentage(&self, toggle: T) -> Option<f64> {
+        self.rollouts[self.toggle_id(&toggle)].map(|buckets| buckets as f64 / 100.0)
+    }
+
+    /// Evaluate `toggle` for a context key (user id, tenant id, ...). During a rollout the
+    /// key is hashed with the toggle name, so a key keeps its decision as the percentage
+    /// grows and different toggles select different keys. Without rollout this is `get`. Same
+    /// as `get_for`, recording the exposure in the sink if any.
+    ///
+    /// This operation is *O*(*1*) plus the hash of the key.
+    pub fn rollout(&self, toggle: T, key: &str) -> bool {
+        self.evaluate(self.toggle_id(&toggle), toggle.as_ref(), key)
+    }
+
+    /// Decide the toggle `name` for a context key, recording the exposure in the sink if any.
+    pub(crate) fn evaluate(&self, toggle_id: usize, name: &str, key: &str) -> bool {
+        let decision = match self.rollouts[toggle_id] {
+            Some(buckets) => {
+                let bucket = hash::fnv1a(format!("{}:{}", name, key).as_bytes()) % BUCKETS;
+                bucket < buckets as u64
+            }
+            None => self.get(toggle_id),
+        };
+        if let Some(sink) = &self.exposure_sink {
+            sink.record(&Exposure {
+                toggle: name.to_string(),
+                key_hash: hash::fnv1a(key.as_bytes()),
+                decision,
+                timestamp: SystemTime::now(),
+            });
+        }
+        decision
+    }
+
+    /// Start the rollout of a toggle over `buckets`, unless it is a startup-only toggle changed
+    /// after startup. Returns false if the change is rejected.
+    pub(crate) fn set_rollout_by_id(
+        &mut self,
+        toggle_id: usize,
+        buckets: u16,
+        provenance: Provenance,
+    ) -> bool {
+        if self.rejects_rollout(toggle_id, buckets) {
+            return false;
+        }
+        if self.rollouts[toggle_id] == Some(buckets) {
+            self.provenance[toggle_id] = provenance;
+            return true;
+        }
+        self.assign(toggle_id, rollout_value(buckets), provenance);
+        self.rollouts[toggle_id] = Some(buckets);
+        true
+    }
+}
+
+/// Number of buckets enabled by a percentage, clamped to 0..=100.
+pub(crate) fn buckets(percentage: f64) -> u16 {
+    (percentage.clamp(0.0, 100.0) * 100.0).round() as u16
+}
+
+/// Value read by `get` during a rollout over `buckets`, enabled only at 100 percent.
+pub(crate) fn rollout_value(buckets: u16) -> bool {
+    buckets as u64 == BUCKETS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_rollout() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        assert!(!toggles.rollout(TestToggles::Toggle1, "user-1"));
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), None);
+
+        toggles.set_rollout(TestToggles::Toggle1, 37.0);
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), Some(37.0));
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+        let enabled = (0..10_000)
+            .filter(|user| toggles.rollout(TestToggles::Toggle1, &format!("user-{}", user)))
+            .count();
+        assert!(
+            (3_400..4_000).contains(&enabled),
+            "{} keys enabled",
+            enabled
+        );
+
+        let before: Vec<bool> = (0..100)
+            .map(|user| toggles.rollout(TestToggles::Toggle1, &format!("user-{}", user)))
+            .collect();
+        toggles.set_rollout(TestToggles::Toggle1, 60.0);
+        for (user, enabled) in before.into_iter().enumerate() {
+            if enabled {
+                assert!(toggles.rollout(TestToggles::Toggle1, &format!("user-{}", user)));
+            }
+        }
+
+        toggles.set_rollout(TestToggles::Toggle1, 100.0);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        toggles.set(TestToggles::Toggle1 as usize, false);
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), None);
+        assert!(!toggles.rollout(TestToggles::Toggle1, "user-1"));
+    }
+
+    #[test]
+    fn test_rollout_records_exposure() {
+        use std::sync::{Arc, Mutex};
+
+        let exposures = Arc::new(Mutex::new(Vec::new()));
+        let recorded = exposures.clone();
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_exposure_sink(move |exposure: &Exposure| {
+            recorded.lock().unwrap().push(exposure.decision)
+        });
+        toggles.set_rollout(TestToggles::Toggle1, 50.0);
+        let decisions: Vec<bool> = (0..100)
+            .map(|user| {
+                let key = format!("user-{}", user);
+                let decision = toggles.rollout(TestToggles::Toggle1, &key);
+                assert_eq!(
+                    toggles.get_for(TestToggles::Toggle1 as usize, &key),
+                    decision
+                );
+                decision
+            })
+            .collect();
+        assert!(decisions.contains(&true) && decisions.contains(&false));
+        let exposures = exposures.lock().unwrap();
+        assert_eq!(exposures.len(), 200);
+        assert!(exposures.chunks(2).all(|pair| pair[0] == pair[1]));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::ToggleError;
+use ron::{Number, Value};
+use yaml_rust::Yaml;
+
+/// Parse the toggles defined in a RON document, a map `{"FeatureA": true}` or an anonymous
+/// struct `(FeatureA: true)`, in document order.
+pub(crate) fn parse(content: &str) -> Result<Vec<(String, bool)>, ToggleError> {
+    parse_values(content)?
+        .into_iter()
+        .map(|(key, value)| {
+            let value = to_bool(&key, &value)?;
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Parse the keys of a RON document like `parse`, keeping their values.
+pub(crate) fn parse_values(content: &str) -> Result<Vec<(String, Value)>, ToggleError> {
+    let document: Value = ron::from_str(content).map_err(|e| ToggleError::Ron(e.to_string()))?;
+    let Value::Map(map) = document else {
+        return Err(ToggleError::TypeMismatch {
+            key: "root document".to_string(),
+            expected: "a map".to_string(),
+        });
+    };
+    map.iter()
+        .map(|(key, value)| {
+            let Value::String(key) = key else {
+                return Err(ToggleError::TypeMismatch {
+                    key: format!("key {:?}", key),
+                    expected: "a string".to_string(),
+                });
+            };
+            Ok((key.clone(), value.clone()))
+        })
+        .collect()
+}
+
+/// Convert a RON value into a yaml node, a bad value for maps, sequences and the like.
+pub(crate) fn to_yaml(value: &Value) -> Yaml {
+    match value {
+        Value::Bool(value) => Yaml::Boolean(*value),
+        Value::Number(Number::I8(value)) => Yaml::Integer((*value).into()),
+        Value::Number(Number::I16(value)) => Yaml::Integer((*value).into()),
+        Value::Number(Number::I32(value)) => Yaml::Integer((*value).into()),
+        Value::Number(Number::I64(value)) => Yaml::Integer(*value),
+        Value::Number(Number::U8(value)) => Yaml::Integer((*value).into()),
+        Value::Number(Number::U16(value)) => Yaml::Integer((*value).into()),
+        Value::Number(Number::U32(value)) => Yaml::Integer((*value).into()),
+        Value::Number(Number::U64(value)) => {
+            i64::try_from(*value).map_or(Yaml::BadValue, Yaml::Integer)
+        }
+        Value::Number(number) => Yaml::Real(number.into_f64().to_string()),
+        Value::String(value) => Yaml::String(value.clone()),
+        _ => Yaml::BadValue,
+    }
+}
+
+fn to_bool(key: &str, value: &Value) -> Result<bool, ToggleError> {
+    match value {
+        Value::Bool(value) => Ok(*value),
+        Value::Number(Number::U8(1) | Number::I32(1)) => Ok(true),
+        Value::Number(Number::U8(0) | Number::I32(0)) => Ok(false),
+        _ => Err(ToggleError::TypeMismatch {
+            key: format!("value of {}", key),
+            expected: "true, false, 0 or 1".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("(FeatureB: true, FeatureA: 0)").unwrap(),
+            vec![
+                ("FeatureB".to_string(), true),
+                ("FeatureA".to_string(), false)
+            ]
+        );
+        assert_eq!(
+            parse(r#"{"FeatureA": false}"#).unwrap(),
+            vec![("FeatureA".to_string(), false)]
+        );
+        assert!(matches!(
+            parse("(FeatureA: 2)"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            parse("[true]"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+        assert!(matches!(parse("(FeatureA: "), Err(ToggleError::Ron(_))));
+    }
+}
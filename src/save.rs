@@ -0,0 +1,717 @@
+#[cfg(feature = "yaml")]
+use crate::{json, SourceFormat, ToggleError};
+use crate::{naming, EnumToggles};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Rewrite the values of the `Name: value` lines of a yaml document in place, keeping
+/// comments, blank lines and key ordering. `resolve` maps a key, prefixed with its
+/// namespaces, to the index of its value, so aliases and nested keys are rewritten rather
+/// than declared again. Toggles absent from the document are appended at the end, a document
+/// that isn't a block mapping, e.g. `{Toggle1: 1}`, is rendered again instead.
+pub(crate) fn rewrite_yaml(
+    content: &str,
+    values: &[(&str, String)],
+    resolve: impl Fn(&str) -> Option<usize>,
+) -> String {
+    let mut written = vec![false; values.len()];
+    let mut output = String::with_capacity(content.len());
+    if is_block_mapping(content) {
+        let mut namespaces: Vec<(usize, String)> = Vec::new();
+        for line in content.lines() {
+            match rewrite_line(line, &mut namespaces, values, &resolve) {
+                Some((index, rewritten)) => {
+                    written[index] = true;
+                    output.push_str(&rewritten);
+                }
+                None => output.push_str(line),
+            }
+            output.push('\n');
+        }
+    }
+    for ((name, value), written) in values.iter().zip(written) {
+        if !written {
+            output.push_str(&format!("{}: {}\n", name, value));
+        }
+    }
+    output
+}
+
+/// True if the first statement of a yaml document isn't a flow collection.
+fn is_block_mapping(content: &str) -> bool {
+    !content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("---"))
+        .is_some_and(|line| line.starts_with(['{', '[']))
+}
+
+/// Rewrite a `Name: value  # comment` line if `Name` is a toggle, keeping the comment.
+/// `namespaces` holds the indentation and name of the enclosing maps, `payments:` followed by
+/// an indented `NewCheckout: 1` declaring `PaymentsNewCheckout`.
+fn rewrite_line(
+    line: &str,
+    namespaces: &mut Vec<(usize, String)>,
+    values: &[(&str, String)],
+    resolve: impl Fn(&str) -> Option<usize>,
+) -> Option<(usize, String)> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with(['#', '-']) {
+        return None;
+    }
+    let indent = line.len() - trimmed.len();
+    while namespaces.last().is_some_and(|(level, _)| *level >= indent) {
+        namespaces.pop();
+    }
+    let (key, rest) = line.split_once(':')?;
+    let name = key.trim().trim_matches(|c| c == '"' || c == '\'');
+    let name = match namespaces.last() {
+        Some((_, namespace)) => format!("{}{}", namespace, naming::capitalize(name)),
+        None => name.to_string(),
+    };
+    let comment = rest.find(" #").map(|i| &rest[i..]).unwrap_or("");
+    if rest[..rest.len() - comment.len()].trim().is_empty() {
+        namespaces.push((indent, naming::capitalize(&name)));
+        return None;
+    }
+    let index = resolve(&name)?;
+    Some((index, format!("{}: {}{}", key, values[index].1, comment)))
+}
+
+/// Render toggle values in the given format, as read back by the matching loader.
+#[cfg(feature = "yaml")]
+pub(crate) fn render(format: &SourceFormat, values: &[(&str, bool)]) -> String {
+    let lines = || -> String {
+        values
+            .iter()
+            .filter_map(|(name, value)| render_line(format, name, *value))
+            .collect()
+    };
+    match format {
+        SourceFormat::Json => {
+            let members: Vec<(&str, String)> = values
+                .iter()
+                .map(|(name, value)| (*name, value.to_string()))
+                .collect();
+            render_json(&members)
+        }
+        #[cfg(feature = "toml")]
+        SourceFormat::Toml(Some(table)) => format!("[{}]\n{}", table, lines()),
+        #[cfg(feature = "ron")]
+        SourceFormat::Ron => format!(
+            "(\n{})\n",
+            values
+                .iter()
+                .map(|(name, value)| format!("    {}: {},\n", name, value))
+                .collect::<String>()
+        ),
+        _ => lines(),
+    }
+}
+
+/// Render a JSON object of toggles, the values being JSON literals.
+#[cfg(feature = "yaml")]
+fn render_json(values: &[(&str, String)]) -> String {
+    let members: Vec<String> = values
+        .iter()
+        .map(|(name, value)| format!("  {}: {}", json::quote(name), value))
+        .collect();
+    format!("{{\n{}\n}}\n", members.join(",\n"))
+}
+
+/// Render one toggle as a line of a line-based format, `None` for JSON and RON.
+#[cfg(feature = "yaml")]
+fn render_line(format: &SourceFormat, name: &str, value: bool) -> Option<String> {
+    let line = match format {
+        SourceFormat::Yaml => format!("{}: {}\n", name, value as u8),
+        #[cfg(feature = "toml")]
+        SourceFormat::Toml(_) => format!("{} = {}\n", name, value),
+        SourceFormat::Dotenv(prefix) => format!(
+            "{}{}={}\n",
+            prefix,
+            naming::to_screaming_snake_case(name),
+            value as u8
+        ),
+        SourceFormat::Ini => format!("{} = {}\n", name, value as u8),
+        SourceFormat::Properties => format!("{}={}\n", name, value as u8),
+        _ => return None,
+    };
+    Some(line)
+}
+
+/// Rewrite the values of an existing document in place, keeping comments, blank lines,
+/// unrelated keys and key ordering. Toggles absent from the document are added to the section
+/// they are read from. `None` for JSON and RON, which are rendered again instead, and yaml,
+/// which is rewritten by `rewrite_yaml`.
+#[cfg(feature = "yaml")]
+pub(crate) fn rewrite(
+    format: &SourceFormat,
+    content: &str,
+    values: &[(&str, bool)],
+) -> Option<String> {
+    let (sectioned, target) = match format {
+        #[cfg(feature = "toml")]
+        SourceFormat::Toml(table) => (true, table.as_deref()),
+        SourceFormat::Ini => (true, None),
+        SourceFormat::Dotenv(_) | SourceFormat::Properties => (false, None),
+        _ => return None,
+    };
+    let keys: Vec<String> = values
+        .iter()
+        .map(|(name, _)| match format {
+            SourceFormat::Dotenv(_) => naming::to_screaming_snake_case(name),
+            _ => name.to_string(),
+        })
+        .collect();
+
+    let mut written = vec![false; values.len()];
+    let mut output: Vec<String> = Vec::new();
+    let mut section: Option<String> = None;
+    let mut found = target.is_none();
+    let mut insert_at = 0;
+    let mut source = content.lines();
+    while let Some(line) = source.next() {
+        let trimmed = line.trim();
+        if sectioned {
+            if let Some(name) = trimmed.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(name.trim().to_string());
+                output.push(line.to_string());
+                if section.as_deref() == target {
+                    found = true;
+                    insert_at = output.len();
+                }
+                continue;
+            }
+        }
+        let mut raw = vec![line.to_string()];
+        if matches!(format, SourceFormat::Properties) {
+            while raw.last().is_some_and(|l| l.ends_with('\\')) {
+                match source.next() {
+                    Some(next) => raw.push(next.to_string()),
+                    None => break,
+                }
+            }
+        }
+        let logical: String = raw
+            .iter()
+            .enumerate()
+            .map(|(i, l)| {
+                let l = if i == 0 { l.as_str() } else { l.trim_start() };
+                l.strip_suffix('\\').unwrap_or(l)
+            })
+            .collect();
+        let split = split_line(format, &logical);
+        let in_target = section.as_deref() == target;
+        let key_line = in_target && split.is_some();
+        let located = split
+            .filter(|_| in_target || matches!(format, SourceFormat::Ini))
+            .and_then(|(key, head, tail)| {
+                let key = match format {
+                    SourceFormat::Ini => format!("{}{}", section.as_deref().unwrap_or(""), key),
+                    _ => key,
+                };
+                let index = keys.iter().position(|k| *k == key)?;
+                Some((index, head, tail))
+            });
+        match located {
+            Some((index, head, tail)) => {
+                written[index] = true;
+                let value = values[index].1;
+                let text = match format {
+                    #[cfg(feature = "toml")]
+                    SourceFormat::Toml(_) => value.to_string(),
+                    _ => (value as u8).to_string(),
+                };
+                output.push(format!("{}{}{}", head, text, tail));
+            }
+            None => output.extend(raw),
+        }
+        if key_line {
+            insert_at = output.len();
+        }
+    }
+    if !sectioned {
+        insert_at = output.len();
+    }
+
+    let missing: Vec<String> = values
+        .iter()
+        .zip(&written)
+        .filter(|(_, written)| !**written)
+        .filter_map(|((name, value), _)| render_line(format, name, *value))
+        .map(|line| line.trim_end().to_string())
+        .collect();
+    if found {
+        let rest = output.split_off(insert_at);
+        output.extend(missing);
+        output.extend(rest);
+    } else if !missing.is_empty() {
+        if let Some(table) = target {
+            output.push(format!("[{}]", table));
+        }
+        output.extend(missing);
+    }
+    Some(output.into_iter().map(|line| line + "\n").collect())
+}
+
+/// Split a `key = value` line of a line-based format into its key, the text before the value
+/// and the text after it, e.g. an inline comment. `None` for comments and blank lines.
+#[cfg(feature = "yaml")]
+fn split_line(format: &SourceFormat, line: &str) -> Option<(String, String, String)> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with(['#', ';', '!']) {
+        return None;
+    }
+    let (key, rest) = match format {
+        SourceFormat::Properties => {
+            let split = line.len() - trimmed.len() + trimmed.find(['=', ':', ' ', '\t'])?;
+            let rest = line[split..].trim_start();
+            (
+                &line[..split],
+                rest.strip_prefix(['=', ':']).unwrap_or(rest),
+            )
+        }
+        _ => line.split_once('=')?,
+    };
+    let value = rest.trim_start();
+    let mut head = line[..line.len() - value.len()].to_string();
+    let mut key = key.trim();
+    if let SourceFormat::Dotenv(prefix) = format {
+        key = key.strip_prefix("export ").unwrap_or(key).trim_start();
+        key = key.strip_prefix(prefix.as_str())?;
+    }
+    let key = key.trim_matches('"').to_string();
+    let tail = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) if !matches!(format, SourceFormat::Properties) => {
+            head.push(quote);
+            value[1..]
+                .find(quote)
+                .map(|end| &value[end + 1..])
+                .unwrap_or("")
+        }
+        _ => value.find(" #").map(|i| &value[i..]).unwrap_or(""),
+    };
+    Some((key, head, tail.to_string()))
+}
+
+/// Replace a file through a rename, so a crash never leaves it half written. The content is
+/// synced to a temporary file of the same directory before the rename, and the directory
+/// after it. The permissions of the file are kept and a symlink is followed, replacing its
+/// target. The previous version is kept as `<path>.bak` when `backup` is set.
+fn write_atomically(filepath: &Path, content: &str, backup: bool) -> io::Result<()> {
+    let target = match fs::canonicalize(filepath) {
+        Ok(target) => target,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => filepath.to_path_buf(),
+        Err(error) => return Err(error),
+    };
+    let directory = match target.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => Path::new("."),
+    };
+    let mut temporary = tempfile::NamedTempFile::new_in(directory)?;
+    temporary.write_all(content.as_bytes())?;
+    if let Ok(metadata) = fs::metadata(&target) {
+        temporary
+            .as_file()
+            .set_permissions(metadata.permissions())?;
+    }
+    temporary.as_file().sync_all()?;
+    if backup && target.exists() {
+        let mut previous = filepath.as_os_str().to_owned();
+        previous.push(".bak");
+        fs::copy(&target, previous)?;
+    }
+    temporary.persist(&target).map_err(|error| error.error)?;
+    #[cfg(unix)]
+    fs::File::open(directory)?.sync_all()?;
+    Ok(())
+}
+
+/// Write the toggle values back to files.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Keep the previous version of a file as `<path>.bak` when `update_file`, `save_to_file`,
+    /// `save` or `write_template` replace it. Disabled by default.
+    pub fn set_keep_backup(&mut self, enabled: bool) {
+        self.keep_backup = enabled;
+    }
+
+    /// Update the values of an existing yaml file in place, preserving its comments and key
+    /// ordering so machine-applied flips don't destroy the human-written documentation.
+    /// Toggles missing from the file are appended. Same as `save_to_file`.
+    pub fn update_file(&self, filepath: &str) -> io::Result<()> {
+        self.save_to_file(filepath)
+    }
+
+    /// Write every toggle as a `Name: 0|1` line, or `Name: 37%` during a rollout, replacing
+    /// the file through a rename so a crash never leaves it half written. An existing file is
+    /// edited in place, keeping its comments and key ordering.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn save_to_file(&self, filepath: impl AsRef<Path>) -> io::Result<()> {
+        let filepath = filepath.as_ref();
+        let content = match fs::read_to_string(filepath) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let toggles: Vec<T> = T::iter().collect();
+        let values = self.yaml_values(&toggles);
+        let content = rewrite_yaml(&content, &values, |key| self.key_id(key));
+        write_atomically(filepath, &content, self.keep_backup)
+    }
+
+    /// Write every toggle with its default value, preceded by its description as a `#`
+    /// comment, as a starting file for a new environment. Current values are ignored.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn write_template(&self, filepath: impl AsRef<Path>) -> io::Result<()> {
+        let mut content = String::new();
+        for (toggle_id, toggle) in T::iter().enumerate() {
+            if let Some(description) = &self.metadata[toggle_id].description {
+                for line in description.lines() {
+                    content.push_str(&format!("# {}\n", line));
+                }
+            }
+            content.push_str(&format!(
+                "{}: {}\n",
+                toggle.as_ref(),
+                self.defaults[toggle_id] as u8
+            ));
+        }
+        write_atomically(filepath.as_ref(), &content, self.keep_backup)
+    }
+
+    /// Write every toggle back to the last file loaded, in the format it was read in, so a
+    /// JSON file stays JSON and a `.env` file keeps its prefix. Line-based formats are edited
+    /// in place, keeping comments, unrelated keys and key ordering. Rollouts are written as
+    /// `37%` in yaml and JSON files, the other formats can't hold them and fail to save.
+    ///
+    /// This operation is *O*(*n*).
+    #[cfg(feature = "yaml")]
+    pub fn save(&self) -> Result<(), ToggleError> {
+        let (filepath, format) = self.saved_source()?;
+        let existing = match fs::read_to_string(filepath) {
+            Ok(content) => Some(content),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => return Err(error.into()),
+        };
+        let toggles: Vec<T> = T::iter().collect();
+        let content = match format {
+            SourceFormat::Yaml => {
+                let values = self.yaml_values(&toggles);
+                rewrite_yaml(&existing.unwrap_or_default(), &values, |key| {
+                    self.key_id(key)
+                })
+            }
+            SourceFormat::Json => {
+                let values: Vec<(&str, String)> = toggles
+                    .iter()
+                    .enumerate()
+                    .map(|(toggle_id, toggle)| {
+                        let value = match self.rollouts[toggle_id] {
+                            Some(buckets) => json::quote(&percentage(buckets)),
+                            None => self.get(toggle_id).to_string(),
+                        };
+                        (toggle.as_ref(), value)
+                    })
+                    .collect();
+                render_json(&values)
+            }
+            _ => {
+                if let Some(toggle_id) = self.rollouts.iter().position(Option::is_some) {
+                    return Err(ToggleError::Serialization(format!(
+                        "{} is rolled out, which {:?} files can't hold",
+                        toggles[toggle_id].as_ref(),
+                        format
+                    )));
+                }
+                let values: Vec<(&str, bool)> = toggles
+                    .iter()
+                    .enumerate()
+                    .map(|(toggle_id, toggle)| (toggle.as_ref(), self.get(toggle_id)))
+                    .collect();
+                existing
+                    .and_then(|content| rewrite(format, &content, &values))
+                    .unwrap_or_else(|| render(format, &values))
+            }
+        };
+        write_atomically(Path::new(filepath), &content, self.keep_backup)?;
+        Ok(())
+    }
+
+    /// Toggle id of a key of a yaml file, resolved like the loaders do.
+    fn key_id(&self, key: &str) -> Option<usize> {
+        #[cfg(feature = "yaml")]
+        {
+            self.resolve_key(key)
+        }
+        #[cfg(not(feature = "yaml"))]
+        {
+            self.id_by_name(key)
+        }
+    }
+
+    /// Value of every toggle as written in yaml files, `0`, `1` or `37%` during a rollout.
+    fn yaml_values<'a>(&self, toggles: &'a [T]) -> Vec<(&'a str, String)> {
+        toggles
+            .iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| {
+                let value = match self.rollouts[toggle_id] {
+                    Some(buckets) => percentage(buckets),
+                    None => (self.get(toggle_id) as u8).to_string(),
+                };
+                (toggle.as_ref(), value)
+            })
+            .collect()
+    }
+}
+
+/// Rollout percentage of a number of buckets, as written in files, e.g. `37%`.
+fn percentage(buckets: u16) -> String {
+    format!("{}%", buckets as f64 / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToggleMetadata;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+        Toggle3,
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_render() {
+        let values = [("FeatureA", true), ("FeatureB", false)];
+        assert_eq!(
+            render(&SourceFormat::Properties, &values),
+            "FeatureA=1\nFeatureB=0\n"
+        );
+        assert_eq!(
+            render(&SourceFormat::Ini, &values),
+            "FeatureA = 1\nFeatureB = 0\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_rewrite() {
+        let values = [
+            ("FeatureA", true),
+            ("CheckoutNewFlow", false),
+            ("FeatureB", true),
+        ];
+        assert_eq!(
+            rewrite(
+                &SourceFormat::Ini,
+                "; legacy\nFeatureA = 0\n\n[Checkout]\nNewFlow=1\n",
+                &values
+            )
+            .unwrap(),
+            "; legacy\nFeatureA = 1\nFeatureB = 1\n\n[Checkout]\nNewFlow=0\n"
+        );
+        assert_eq!(
+            rewrite(
+                &SourceFormat::Dotenv("APP_".to_string()),
+                "DATABASE_URL=postgres://db\nexport APP_FEATURE_A=\"off\" # QA\n",
+                &values
+            )
+            .unwrap(),
+            "DATABASE_URL=postgres://db\nexport APP_FEATURE_A=\"1\" # QA\n\
+             APP_CHECKOUT_NEW_FLOW=0\nAPP_FEATURE_B=1\n"
+        );
+        assert_eq!(
+            rewrite(
+                &SourceFormat::Properties,
+                "! flags\nFeatureB : \\\n    false\nFeatureA 0\n",
+                &values
+            )
+            .unwrap(),
+            "! flags\nFeatureB : 1\nFeatureA 1\nCheckoutNewFlow=0\n"
+        );
+        assert_eq!(rewrite(&SourceFormat::Json, "{}", &values), None);
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_rewrite_toml() {
+        let content = "[server]\nport = 8080\n\n[toggles]\n# launch\nFeatureA = false # QA\n\n\
+            [database]\nurl = \"postgres://db\"\n";
+        assert_eq!(
+            rewrite(
+                &SourceFormat::Toml(Some("toggles".to_string())),
+                content,
+                &[("FeatureA", true), ("FeatureB", false)]
+            )
+            .unwrap(),
+            "[server]\nport = 8080\n\n[toggles]\n# launch\nFeatureA = true # QA\nFeatureB = false\n\n\
+             [database]\nurl = \"postgres://db\"\n"
+        );
+        assert_eq!(
+            rewrite(
+                &SourceFormat::Toml(Some("toggles".to_string())),
+                "[server]\nport = 8080\n",
+                &[("FeatureA", true)]
+            )
+            .unwrap(),
+            "[server]\nport = 8080\n[toggles]\nFeatureA = true\n"
+        );
+    }
+
+    #[test]
+    fn test_save_to_file() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        toggles.set_rollout(TestToggles::Toggle3, 12.5);
+        toggles.save_to_file(&filepath).unwrap();
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "Toggle1: 0\nToggle2: 1\nToggle3: 12.5%\n"
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        fs::write(
+            &filepath,
+            "# rollout\nToggle3: 0\nToggle1: 1 # kill-switch\n",
+        )
+        .unwrap();
+        toggles.save_to_file(&filepath).unwrap();
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "# rollout\nToggle3: 12.5%\nToggle1: 0 # kill-switch\nToggle2: 1\n"
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_save_to_file_with_backup() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_keep_backup(true);
+        toggles.save_to_file(&filepath).unwrap();
+        assert!(!dir.path().join("toggles.yaml.bak").exists());
+
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.save_to_file(&filepath).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("toggles.yaml.bak")).unwrap(),
+            "Toggle1: 0\nToggle2: 0\nToggle3: 0\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "Toggle1: 1\nToggle2: 0\nToggle3: 0\n"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_save_to_file_keeps_permissions_and_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        fs::write(&filepath, "Toggle1: 0\n").unwrap();
+        fs::set_permissions(&filepath, fs::Permissions::from_mode(0o640)).unwrap();
+        let link = dir.path().join("current.yaml");
+        std::os::unix::fs::symlink(&filepath, &link).unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.save_to_file(&link).unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "Toggle1: 1\nToggle2: 0\nToggle3: 0\n"
+        );
+        assert_eq!(
+            fs::metadata(&filepath).unwrap().permissions().mode() & 0o777,
+            0o640
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_write_template() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_default(TestToggles::Toggle1 as usize, true);
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        toggles.describe(
+            TestToggles::Toggle3,
+            ToggleMetadata {
+                description: Some("New search ranking".to_string()),
+                ..Default::default()
+            },
+        );
+        toggles.write_template(&filepath).unwrap();
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "Toggle1: 1\nToggle2: 0\n# New search ranking\nToggle3: 0\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_yaml() {
+        let values = [
+            ("Toggle1", "0".to_string()),
+            ("Toggle2", "1".to_string()),
+            ("Toggle3", "1".to_string()),
+        ];
+        let resolve = |key: &str| match key {
+            "OldToggle3" => Some(2),
+            key => values.iter().position(|(name, _)| *name == key),
+        };
+        let content =
+            "# Search toggles\nToggle2: 0 # enabled for the launch\n\n\"Toggle1\": 1\nOther: 1\n";
+        assert_eq!(
+            rewrite_yaml(content, &values, resolve),
+            "# Search toggles\nToggle2: 1 # enabled for the launch\n\n\"Toggle1\": 0\nOther: 1\nToggle3: 1\n"
+        );
+        assert_eq!(
+            rewrite_yaml(
+                "toggle:\n  \"1\": 1 # nested\n  \"2\": 0\nOldToggle3: 0\n",
+                &values,
+                resolve
+            ),
+            "toggle:\n  \"1\": 0 # nested\n  \"2\": 1\nOldToggle3: 1\n"
+        );
+        assert_eq!(
+            rewrite_yaml("{Toggle1: 1}\n", &values, resolve),
+            "Toggle1: 0\nToggle2: 1\nToggle3: 1\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_save_to_file_resolves_keys() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("toggles.yaml");
+        fs::write(&filepath, "toggle_1: 0\nLegacyToggle2: 0\nToggle3: 0\n").unwrap();
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_loose_matching(true);
+        toggles.add_deprecated_name("LegacyToggle2", TestToggles::Toggle2);
+        toggles.set_all_true();
+        toggles.save_to_file(&filepath).unwrap();
+        assert_eq!(
+            fs::read_to_string(&filepath).unwrap(),
+            "toggle_1: 1\nLegacyToggle2: 1\nToggle3: 1\n"
+        );
+    }
+}
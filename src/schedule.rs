@@ -0,0 +1,85 @@
+use crate::{EnumToggles, Provenance};
+use std::time::SystemTime;
+
+/// Toggle values queued by `EnumToggles::schedule`.
+#[derive(Debug, Clone)]
+pub(crate) struct Changeset {
+    at: SystemTime,
+    values: Vec<(usize, bool)>,
+}
+
+/// Changes planned ahead of time, for coordinated launches.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Queue a changeset applied at once by `apply_due_changesets` when `at` is reached.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn schedule(&mut self, at: SystemTime, values: Vec<(T, bool)>) {
+        let values = values
+            .iter()
+            .map(|(toggle, value)| (self.toggle_id(toggle), *value))
+            .collect();
+        self.scheduled.push(Changeset { at, values });
+        self.scheduled.sort_by_key(|changeset| changeset.at);
+    }
+
+    /// Number of changesets waiting for their time.
+    pub fn pending_changesets(&self) -> usize {
+        self.scheduled.len()
+    }
+
+    /// Apply the changesets whose time has come, oldest first, returning how many were
+    /// applied. Call it periodically, e.g. along with file reloads.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn apply_due_changesets(&mut self) -> usize {
+        let now = SystemTime::now();
+        let due = self
+            .scheduled
+            .partition_point(|changeset| changeset.at <= now);
+        let changesets: Vec<Changeset> = self.scheduled.drain(..due).collect();
+        for changeset in &changesets {
+            for &(toggle_id, value) in &changeset.values {
+                self.assign(toggle_id, value, Provenance::Scheduled);
+            }
+        }
+        changesets.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EnumToggles, Provenance};
+    use std::time::{Duration, SystemTime};
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_apply_due_changesets() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        let now = SystemTime::now();
+        toggles.schedule(
+            now + Duration::from_secs(3600),
+            vec![(TestToggles::Toggle1, false)],
+        );
+        toggles.schedule(
+            now - Duration::from_secs(1),
+            vec![(TestToggles::Toggle1, true), (TestToggles::Toggle2, true)],
+        );
+        assert_eq!(toggles.pending_changesets(), 2);
+
+        assert_eq!(toggles.apply_due_changesets(), 1);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(toggles.status()[0].provenance, Provenance::Scheduled);
+        assert_eq!(toggles.pending_changesets(), 1);
+        assert_eq!(toggles.apply_due_changesets(), 0);
+    }
+}
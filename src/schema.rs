@@ -0,0 +1,90 @@
+use crate::{json, EnumToggles};
+
+/// Values accepted for a toggle: a boolean, `0`/`1`, one of the strings read by the loaders,
+/// or a rollout percentage written `37%` or `0.37`.
+const VALUE_SCHEMA: &str = "{\"anyOf\":[{\"type\":\"boolean\"},{\"type\":\"number\",\"minimum\":0,\"maximum\":1},{\"enum\":[\"true\",\"false\",\"yes\",\"no\",\"on\",\"off\",\"1\",\"0\",\"True\",\"False\",\"Yes\",\"No\",\"On\",\"Off\",\"TRUE\",\"FALSE\",\"YES\",\"NO\",\"ON\",\"OFF\"]},{\"type\":\"string\",\"pattern\":\"^\\\\s*(100(\\\\.0+)?|\\\\d{1,2}(\\\\.\\\\d+)?)\\\\s*%\\\\s*$\"}]}";
+
+/// Schema of the toggle files.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Generate a JSON Schema (draft 2020-12) of the toggle file: a map whose keys are the
+    /// toggle names and aliases, with their descriptions, and whose values are those read by
+    /// `load_from_file`. Unknown keys are rejected, as `load_from_file_strict` does, and
+    /// namespaced toggles are expected in their flat form.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn json_schema(&self) -> String {
+        let mut properties: Vec<String> = T::iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| {
+                let description = match &self.metadata[toggle_id].description {
+                    Some(description) => format!("\"description\":{},", json::quote(description)),
+                    None => String::new(),
+                };
+                format!(
+                    "{}:{{{}\"$ref\":\"#/$defs/value\"}}",
+                    json::quote(toggle.as_ref()),
+                    description
+                )
+            })
+            .collect();
+        let mut aliases: Vec<&String> = self
+            .name_index
+            .keys()
+            .filter(|name| !T::iter().any(|toggle| toggle.as_ref() == name.as_str()))
+            .collect();
+        aliases.sort();
+        properties.extend(aliases.into_iter().map(|alias| {
+            format!(
+                "{}:{{\"deprecated\":true,\"$ref\":\"#/$defs/value\"}}",
+                json::quote(alias)
+            )
+        }));
+        properties.push(
+            "\"include\":{\"description\":\"Files read before this one\",\"anyOf\":[{\"type\":\"string\"},{\"type\":\"array\",\"items\":{\"type\":\"string\"}}]}"
+                .to_string(),
+        );
+        format!(
+            "{{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"type\":\"object\",\"properties\":{{{}}},\"additionalProperties\":false,\"$defs\":{{\"value\":{}}}}}",
+            properties.join(","),
+            VALUE_SCHEMA
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EnumToggles, ToggleMetadata};
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.describe(
+            TestToggles::Toggle2,
+            ToggleMetadata {
+                description: Some("New \"search\" ranking".to_string()),
+                ..Default::default()
+            },
+        );
+        toggles.add_alias("OldToggle1", TestToggles::Toggle1);
+        let schema = toggles.json_schema();
+        assert!(schema.starts_with(
+            "{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"type\":\"object\",\"properties\":{\"Toggle1\":{\"$ref\":\"#/$defs/value\"},\"Toggle2\":{\"description\":\"New \\\"search\\\" ranking\",\"$ref\":\"#/$defs/value\"},\"OldToggle1\":{\"deprecated\":true,\"$ref\":\"#/$defs/value\"},\"include\":"
+        ));
+        assert!(
+            schema.contains("},\"additionalProperties\":false,\"$defs\":{\"value\":{\"anyOf\":")
+        );
+        assert!(schema.contains(
+            "\"pattern\":\"^\\\\s*(100(\\\\.0+)?|\\\\d{1,2}(\\\\.\\\\d+)?)\\\\s*%\\\\s*$\""
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::EnumToggles;
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::future::Future;
+
+tokio::task_local! {
+    static OVERRIDES: TaskOverrides;
+}
+
+/// Toggle values overriding the shared state for a single task, e.g. a request tagged by a
+/// debug header, without affecting concurrent tasks running on the same threads.
+#[derive(Debug, Clone, Default)]
+pub struct TaskOverrides {
+    values: HashMap<(TypeId, usize), bool>,
+}
+
+impl TaskOverrides {
+    /// Create a new instance of `TaskOverrides` without any override.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the value of a toggle, chainable.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn set<T>(mut self, toggle: T, value: bool) -> Self
+    where
+        T: strum::IntoEnumIterator + PartialEq + 'static,
+    {
+        let toggle_id = T::iter()
+            .position(|x| x == toggle)
+            .expect("Every variant is yielded by its own iterator");
+        self.values.insert((TypeId::of::<T>(), toggle_id), value);
+        self
+    }
+
+    /// Run `future` with these overrides, propagated across its `.await` points. Overrides of
+    /// an enclosing scope still apply unless replaced.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        let mut overrides = OVERRIDES
+            .try_with(|outer| outer.clone())
+            .unwrap_or_default();
+        overrides.values.extend(self.values);
+        OVERRIDES.scope(overrides, future).await
+    }
+}
+
+/// Task-local overrides.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Get the bool value of a toggle by toggle id, as overridden for the current task by
+    /// `TaskOverrides::scope`.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn get_scoped(&self, toggle_id: usize) -> bool {
+        OVERRIDES
+            .try_with(|overrides| {
+                overrides
+                    .values
+                    .get(&(TypeId::of::<T>(), toggle_id))
+                    .copied()
+            })
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| self.get(toggle_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    async fn yield_now() {
+        tokio::task::yield_now().await;
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle2 as usize, true);
+
+        let overrides = TaskOverrides::new().set(TestToggles::Toggle1, true);
+        overrides
+            .scope(async {
+                yield_now().await;
+                assert!(toggles.get_scoped(TestToggles::Toggle1 as usize));
+                assert!(toggles.get_scoped(TestToggles::Toggle2 as usize));
+
+                TaskOverrides::new()
+                    .set(TestToggles::Toggle2, false)
+                    .scope(async {
+                        assert!(toggles.get_scoped(TestToggles::Toggle1 as usize));
+                        assert!(!toggles.get_scoped(TestToggles::Toggle2 as usize));
+                    })
+                    .await;
+            })
+            .await;
+
+        assert!(!toggles.get_scoped(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle1 as usize));
+    }
+}
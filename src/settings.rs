@@ -0,0 +1,371 @@
+#[cfg(feature = "yaml")]
+use crate::{dotenv, ini, json5, properties, ParseLimits, SourceFormat, ToggleError};
+#[cfg(feature = "yaml")]
+use log::warn;
+use std::{fmt, marker::PhantomData};
+#[cfg(feature = "yaml")]
+use std::{fs, path::Path, time::Duration};
+#[cfg(feature = "yaml")]
+use yaml_rust::{Yaml, YamlLoader};
+
+/// A value that can be stored in [`EnumSettings`] and read from a file.
+#[cfg(feature = "yaml")]
+pub trait SettingValue: Sized {
+    /// Convert a parsed yaml node into the value, `None` if the node has the wrong type.
+    fn from_yaml(value: &Yaml) -> Option<Self>;
+}
+
+#[cfg(feature = "yaml")]
+impl SettingValue for bool {
+    fn from_yaml(value: &Yaml) -> Option<Self> {
+        crate::yaml::to_bool(value)
+    }
+}
+
+#[cfg(feature = "yaml")]
+macro_rules! impl_setting_value_int {
+    ($($t:ty),*) => {
+        $(
+            impl SettingValue for $t {
+                fn from_yaml(value: &Yaml) -> Option<Self> {
+                    value.as_i64().and_then(|i| <$t>::try_from(i).ok())
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "yaml")]
+impl_setting_value_int!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+#[cfg(feature = "yaml")]
+impl SettingValue for f64 {
+    fn from_yaml(value: &Yaml) -> Option<Self> {
+        match value {
+            Yaml::Real(_) => value.as_f64(),
+            Yaml::Integer(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl SettingValue for String {
+    fn from_yaml(value: &Yaml) -> Option<Self> {
+        value.as_str().map(str::to_string)
+    }
+}
+
+/// Integers and reals are read as seconds, strings accept a `ms`, `s`, `m` or `h` suffix
+/// (`250ms`, `5m`).
+#[cfg(feature = "yaml")]
+impl SettingValue for Duration {
+    fn from_yaml(value: &Yaml) -> Option<Self> {
+        match value {
+            Yaml::Integer(i) => u64::try_from(*i).ok().map(Duration::from_secs),
+            Yaml::Real(_) => value
+                .as_f64()
+                .and_then(|f| Duration::try_from_secs_f64(f).ok()),
+            Yaml::String(s) => {
+                let s = s.trim();
+                let (number, unit) = s.split_at(s.find(|c: char| c.is_alphabetic())?);
+                let number: u64 = number.trim().parse().ok()?;
+                match unit {
+                    "ms" => Some(Duration::from_millis(number)),
+                    "s" => Some(Duration::from_secs(number)),
+                    "m" => number.checked_mul(60).map(Duration::from_secs),
+                    "h" => number.checked_mul(3600).map(Duration::from_secs),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Contains a value of type V for each item of the enum T.
+pub struct EnumSettings<T, V> {
+    values: Vec<V>,
+    #[cfg(feature = "yaml")]
+    parse_limits: ParseLimits,
+    _marker: PhantomData<T>,
+}
+
+impl<T, V> Default for EnumSettings<T, V>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + 'static,
+    V: Default + Clone,
+{
+    fn default() -> Self {
+        EnumSettings {
+            values: vec![V::default(); T::iter().count()],
+            #[cfg(feature = "yaml")]
+            parse_limits: ParseLimits::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Handle the value of each item of an enum T.
+impl<T, V> EnumSettings<T, V>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+    V: Clone,
+{
+    /// Create a new instance of `EnumSettings` with all settings set to `value`.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn new(value: V) -> Self {
+        EnumSettings {
+            values: vec![value; T::iter().count()],
+            #[cfg(feature = "yaml")]
+            parse_limits: ParseLimits::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the value of a setting by its name.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn set_by_name(&mut self, setting_name: &str, value: V) {
+        if let Some(setting_id) = T::iter().position(|t| setting_name == t.as_ref()) {
+            self.set(setting_id, value);
+        }
+    }
+
+    /// Set the value of a setting by setting id.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn set(&mut self, setting_id: usize, value: V) {
+        if setting_id >= self.values.len() {
+            panic!(
+                "Out-of-bounds access. The provided setting_id is {}, but the array size is {}. Please use the default enum value.",
+                setting_id,
+                self.values.len()
+            );
+        }
+        self.values[setting_id] = value;
+    }
+
+    /// Get the value of a setting by setting id.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn get(&self, setting_id: usize) -> &V {
+        &self.values[setting_id]
+    }
+}
+
+/// Load the settings values from files.
+#[cfg(feature = "yaml")]
+impl<T, V> EnumSettings<T, V>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+    V: SettingValue + Clone,
+{
+    /// Set all settings value defined in the file, or none of them if one is invalid. The
+    /// file is read like `EnumToggles::load_from_file`: any of its formats, within the parse
+    /// limits.
+    pub fn load_from_file(&mut self, filepath: &str) -> Result<(), ToggleError> {
+        let content = self.parse_limits.read(fs::File::open(filepath)?)?;
+        let format = SourceFormat::detect(Path::new(filepath), &content);
+
+        let mut staged = Vec::new();
+        for (name, value) in parse_nodes(&content, &format, &self.parse_limits)? {
+            let value = V::from_yaml(&value).ok_or_else(|| ToggleError::TypeMismatch {
+                key: format!("value of {}", name),
+                expected: std::any::type_name::<V>().to_string(),
+            })?;
+            match T::iter().position(|t| name == t.as_ref()) {
+                Some(setting_id) => staged.push((setting_id, value)),
+                None => warn!("Unknown setting {} in {}", name, filepath),
+            }
+        }
+        for (setting_id, value) in staged {
+            self.set(setting_id, value);
+        }
+
+        Ok(())
+    }
+
+    /// Bound the size, key count and nesting of the files read by `load_from_file`.
+    pub fn set_parse_limits(&mut self, limits: ParseLimits) {
+        self.parse_limits = limits;
+    }
+}
+
+/// Parse the keys of a document of any format within the limits, their values as yaml nodes.
+/// The values of the line-based formats are read like yaml scalars, `5` being an integer.
+#[cfg(feature = "yaml")]
+fn parse_nodes(
+    content: &str,
+    format: &SourceFormat,
+    limits: &ParseLimits,
+) -> Result<Vec<(String, Yaml)>, ToggleError> {
+    let text = |pairs: Vec<(String, String)>| -> Vec<(String, Yaml)> {
+        pairs
+            .into_iter()
+            .map(|(key, text)| (key, Yaml::from_str(&text)))
+            .collect()
+    };
+    let nodes = match format {
+        SourceFormat::Yaml | SourceFormat::Json => {
+            let content = match format {
+                SourceFormat::Json => json5::strip(content),
+                _ => content.to_string(),
+            };
+            limits.check(&content)?;
+            let docs = YamlLoader::load_from_str(&content)?;
+            let Some(Yaml::Hash(h)) = docs.first() else {
+                return Ok(Vec::new());
+            };
+            h.iter()
+                .map(|(key, value)| {
+                    let name = key.as_str().ok_or_else(|| ToggleError::TypeMismatch {
+                        key: format!("key {:?}", key),
+                        expected: "a string".to_string(),
+                    })?;
+                    Ok((name.to_string(), value.clone()))
+                })
+                .collect::<Result<_, ToggleError>>()?
+        }
+        #[cfg(feature = "toml")]
+        SourceFormat::Toml(table) => {
+            limits.check_size(content)?;
+            crate::toml::parse_values(content, table.as_deref())?
+                .into_iter()
+                .map(|(key, value)| (key, crate::toml::to_yaml(&value)))
+                .collect()
+        }
+        SourceFormat::Dotenv(prefix) => {
+            limits.check_size(content)?;
+            text(dotenv::parse_raw(content, prefix)?)
+        }
+        SourceFormat::Ini => {
+            limits.check_size(content)?;
+            text(ini::parse_raw(content)?)
+        }
+        SourceFormat::Properties => {
+            limits.check_size(content)?;
+            text(properties::parse_raw(content)?)
+        }
+        #[cfg(feature = "ron")]
+        SourceFormat::Ron => {
+            limits.check_size(content)?;
+            crate::ron::parse_values(content)?
+                .into_iter()
+                .map(|(key, value)| (key, crate::ron::to_yaml(&value)))
+                .collect()
+        }
+    };
+    limits.check_key_count(nodes.len())?;
+    Ok(nodes)
+}
+
+/// Diplay all settings and their values.
+impl<T, V> fmt::Debug for EnumSettings<T, V>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + 'static,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (toggle, value) in T::iter().zip(self.values.iter()) {
+            writeln!(f, "{:?} {} ", value, toggle.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "yaml")]
+    use std::io::Write;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestSettings {
+        Retries,
+        Timeout,
+    }
+
+    #[test]
+    fn test_set_by_name() {
+        let mut settings: EnumSettings<TestSettings, u32> = EnumSettings::new(3);
+        settings.set_by_name("Retries", 5);
+        settings.set_by_name("Undefined_Setting", 7);
+        assert_eq!(*settings.get(TestSettings::Retries as usize), 5);
+        assert_eq!(*settings.get(TestSettings::Timeout as usize), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_load_from_file() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Retries: 2").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Timeout: 250ms").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut settings: EnumSettings<TestSettings, Duration> = EnumSettings::default();
+        settings.load_from_file(filepath).unwrap();
+        assert_eq!(
+            *settings.get(TestSettings::Retries as usize),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            *settings.get(TestSettings::Timeout as usize),
+            Duration::from_millis(250)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_load_from_file_invalid_value() {
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Timeout: 10").expect("Unable to write to temporary file");
+        writeln!(temp_file, "Retries: many").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut settings: EnumSettings<TestSettings, u32> = EnumSettings::new(3);
+        assert!(settings.load_from_file(filepath).is_err());
+        assert_eq!(*settings.get(TestSettings::Timeout as usize), 3);
+
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Timeout: 9999999999999999999h")
+            .expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+        let mut settings: EnumSettings<TestSettings, Duration> = EnumSettings::default();
+        assert!(matches!(
+            settings.load_from_file(filepath),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_load_from_file_formats() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let filepath = dir.path().join("settings.properties");
+        fs::write(&filepath, "Retries=5\nTimeout=2m\n").expect("Unable to write file");
+        let filepath = filepath.to_str().unwrap();
+
+        let mut settings: EnumSettings<TestSettings, Duration> = EnumSettings::default();
+        settings.load_from_file(filepath).unwrap();
+        assert_eq!(
+            *settings.get(TestSettings::Retries as usize),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            *settings.get(TestSettings::Timeout as usize),
+            Duration::from_secs(120)
+        );
+
+        settings.set_parse_limits(ParseLimits::new().max_keys(1));
+        assert!(matches!(
+            settings.load_from_file(filepath),
+            Err(ToggleError::LimitExceeded(_))
+        ));
+    }
+}
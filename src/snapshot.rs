@@ -0,0 +1,101 @@
+use crate::{EnumToggles, Provenance};
+use bitvec::prelude::*;
+
+/// Values, rollouts and provenance of the toggles captured by `EnumToggles::snapshot`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToggleSnapshot {
+    values: BitVec,
+    rollouts: Vec<Option<u16>>,
+    provenance: Vec<Provenance>,
+}
+
+/// Capture and roll back the state around risky runtime modifications.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Capture the values, rollouts and provenance of all toggles.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn snapshot(&self) -> ToggleSnapshot {
+        ToggleSnapshot {
+            values: self.toggles_value.clone(),
+            rollouts: self.rollouts.clone(),
+            provenance: self.provenance.clone(),
+        }
+    }
+
+    /// Restore the values, rollouts and provenance captured by `snapshot`.
+    ///
+    /// Panics if the snapshot was taken from an enum with a different number of toggles.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn restore(&mut self, snapshot: &ToggleSnapshot) {
+        if snapshot.values.len() != self.toggles_value.len() {
+            panic!(
+                "Snapshot mismatch. The snapshot holds {} toggles, but the array size is {}.",
+                snapshot.values.len(),
+                self.toggles_value.len()
+            );
+        }
+        for (toggle_id, value) in snapshot.values.iter().by_vals().enumerate() {
+            let provenance = snapshot.provenance[toggle_id].clone();
+            match snapshot.rollouts[toggle_id] {
+                Some(buckets) => self.set_rollout_by_id(toggle_id, buckets, provenance),
+                None => self.assign(toggle_id, value, provenance),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EnumToggles, Provenance};
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum OtherToggles {
+        Toggle1,
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        let snapshot = toggles.snapshot();
+        toggles.set(TestToggles::Toggle1 as usize, false);
+        toggles.set(TestToggles::Toggle2 as usize, true);
+
+        toggles.restore(&snapshot);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.get(TestToggles::Toggle2 as usize));
+        assert_eq!(toggles.status()[1].provenance, Provenance::Default);
+    }
+
+    #[test]
+    fn test_restore_rollout() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_rollout(TestToggles::Toggle1, 37.0);
+        let snapshot = toggles.snapshot();
+        toggles.set_rollout(TestToggles::Toggle1, 80.0);
+        toggles.set_rollout(TestToggles::Toggle2, 10.0);
+
+        toggles.restore(&snapshot);
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), Some(37.0));
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle2), None);
+        assert_eq!(toggles.status()[0].provenance, Provenance::Api);
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot mismatch")]
+    fn test_restore_mismatch() {
+        let snapshot = EnumToggles::<TestToggles>::new().snapshot();
+        EnumToggles::<OtherToggles>::new().restore(&snapshot);
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::Path;
+
+/// Syntax of the toggle documents read by `EnumToggles::load_from_str`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SourceFormat {
+    /// A yaml map, `FeatureA: 1`.
+    #[default]
+    Yaml,
+    /// A JSON or JSON5 object, `{"FeatureA": true}`.
+    Json,
+    /// TOML keys, `FeatureA = true`, at the root or in the given table.
+    #[cfg(feature = "toml")]
+    Toml(Option<String>),
+    /// `.env` lines, `APP_FEATURE_A=1`, only the keys starting with the given prefix being read.
+    Dotenv(String),
+    /// INI keys, `NewFlow = 1`, prefixed with the name of their section.
+    Ini,
+    /// Java properties, `FeatureA=1`.
+    Properties,
+    /// A RON map or anonymous struct, `(FeatureA: true)`.
+    #[cfg(feature = "ron")]
+    Ron,
+}
+
+impl SourceFormat {
+    /// Pick the format of a file from its extension, falling back on the look of its first
+    /// line: `{` for JSON, `(` for RON, `[` for INI, `=` before any `:` for properties, yaml
+    /// otherwise.
+    pub(crate) fn detect(path: &Path, content: &str) -> Self {
+        let extension = path
+            .extension()
+            .or_else(|| path.file_name().filter(|name| *name == ".env"))
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.trim_start_matches('.').to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("yaml" | "yml") => return SourceFormat::Yaml,
+            Some("json" | "json5") => return SourceFormat::Json,
+            #[cfg(feature = "toml")]
+            Some("toml") => return SourceFormat::Toml(None),
+            Some("env") => return SourceFormat::Dotenv(String::new()),
+            Some("ini" | "cfg") => return SourceFormat::Ini,
+            Some("properties") => return SourceFormat::Properties,
+            #[cfg(feature = "ron")]
+            Some("ron") => return SourceFormat::Ron,
+            _ => {}
+        }
+        let line = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with(['#', ';', '!', '/']))
+            .unwrap_or_default();
+        match line.chars().next() {
+            Some('{') => SourceFormat::Json,
+            #[cfg(feature = "ron")]
+            Some('(') => SourceFormat::Ron,
+            Some('[') => SourceFormat::Ini,
+            _ => match (line.find('='), line.find(':')) {
+                (Some(equal), Some(colon)) if equal < colon => SourceFormat::Properties,
+                (Some(_), None) => SourceFormat::Properties,
+                _ => SourceFormat::Yaml,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let detect = |path: &str, content: &str| SourceFormat::detect(Path::new(path), content);
+        assert_eq!(detect("toggles.yml", "{}"), SourceFormat::Yaml);
+        assert_eq!(detect("toggles.JSON", ""), SourceFormat::Json);
+        assert_eq!(
+            detect("config/.env", ""),
+            SourceFormat::Dotenv(String::new())
+        );
+        assert_eq!(detect("app.properties", ""), SourceFormat::Properties);
+        assert_eq!(
+            detect("toggles", "# flags\n{\"FeatureA\": 1}"),
+            SourceFormat::Json
+        );
+        assert_eq!(
+            detect("toggles", "[Checkout]\nNewFlow = 1"),
+            SourceFormat::Ini
+        );
+        assert_eq!(detect("toggles", "FeatureA=1"), SourceFormat::Properties);
+        assert_eq!(
+            detect("toggles", "FeatureA: 1\nUrl: a=b"),
+            SourceFormat::Yaml
+        );
+        assert_eq!(detect("toggles", ""), SourceFormat::Yaml);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::EnumToggles;
+use log::error;
+
+/// Startup-only toggles, gating code paths that cannot safely change at runtime.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Mark a toggle as startup-only: once `finish_startup` is called, any change of its
+    /// value (file reload, `set`, `reset`, `trip`...) is rejected and logged as an error.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn mark_startup_only(&mut self, toggle: T) {
+        let toggle_id = self.toggle_id(&toggle);
+        self.startup_only.set(toggle_id, true);
+    }
+
+    /// True if the toggle was marked with `mark_startup_only`.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn is_startup_only(&self, toggle: T) -> bool {
+        self.startup_only[self.toggle_id(&toggle)]
+    }
+
+    /// End the initialization, freezing the value of the startup-only toggles.
+    pub fn finish_startup(&mut self) {
+        self.startup_finished = true;
+    }
+
+    /// True if changing the toggle to `value` must be rejected, logging it. Ending a running
+    /// rollout is a change even when the value stays the same.
+    pub(crate) fn rejects_change(&self, toggle_id: usize, value: bool) -> bool {
+        let rejected = self.is_frozen(toggle_id)
+            && (self.toggles_value[toggle_id] != value || self.rollouts[toggle_id].is_some());
+        if rejected {
+            if let Some(toggle) = T::iter().nth(toggle_id) {
+                error!(
+                    "Rejected change of startup-only toggle {} to {} after startup",
+                    toggle.as_ref(),
+                    value
+                );
+            }
+        }
+        rejected
+    }
+
+    /// True if starting a rollout of the toggle over `buckets` must be rejected, logging it.
+    /// Any rollout other than the running one is a change.
+    pub(crate) fn rejects_rollout(&self, toggle_id: usize, buckets: u16) -> bool {
+        let rejected = self.is_frozen(toggle_id) && self.rollouts[toggle_id] != Some(buckets);
+        if rejected {
+            if let Some(toggle) = T::iter().nth(toggle_id) {
+                error!(
+                    "Rejected rollout of startup-only toggle {} to {}% after startup",
+                    toggle.as_ref(),
+                    buckets as f64 / 100.0
+                );
+            }
+        }
+        rejected
+    }
+
+    /// True if the toggle is startup-only and the startup is over.
+    fn is_frozen(&self, toggle_id: usize) -> bool {
+        self.startup_finished && self.startup_only[toggle_id]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EnumToggles, Provenance};
+    use std::time::Duration;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_startup_only() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.mark_startup_only(TestToggles::Toggle1);
+        assert!(toggles.is_startup_only(TestToggles::Toggle1));
+        assert!(!toggles.is_startup_only(TestToggles::Toggle2));
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.finish_startup();
+
+        toggles.set(TestToggles::Toggle1 as usize, false);
+        toggles.set(TestToggles::Toggle2 as usize, true);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(toggles.get(TestToggles::Toggle2 as usize));
+
+        toggles.reset_all();
+        toggles.trip(TestToggles::Toggle1, "errors", Duration::from_secs(60));
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        assert!(!toggles.is_tripped(TestToggles::Toggle1));
+        assert_eq!(
+            toggles.status()[TestToggles::Toggle1 as usize].provenance,
+            Provenance::Api
+        );
+
+        toggles.set_rollout(TestToggles::Toggle1, 50.0);
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle1), None);
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+        toggles.set(TestToggles::Toggle2 as usize, false);
+        toggles.mark_startup_only(TestToggles::Toggle2);
+        toggles.set_rollout(TestToggles::Toggle2, 50.0);
+        assert_eq!(toggles.rollout_percentage(TestToggles::Toggle2), None);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::EnumToggles;
+use std::time::SystemTime;
+
+/// Where the current value of a toggle comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Provenance {
+    /// Never changed, or reset to its default value.
+    #[default]
+    Default,
+    /// Set by code through the API.
+    Api,
+    /// Set by the file at the given path.
+    File(String),
+    /// Set by the document held by the given environment variable.
+    Env(String),
+    /// Restored by `revert_last_load`.
+    Reverted,
+    /// Forced off by `trip` for the given reason.
+    Tripped(String),
+    /// Set by a changeset queued with `schedule`.
+    Scheduled,
+}
+
+/// Documentation attached to a toggle with `EnumToggles::describe`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ToggleMetadata {
+    /// What the toggle gates.
+    pub description: Option<String>,
+    /// Team or person responsible for the toggle.
+    pub owner: Option<String>,
+    /// Tracking ticket.
+    pub ticket: Option<String>,
+    /// Date after which the toggle should be removed, e.g. `2025-12-31`.
+    pub expires: Option<String>,
+}
+
+/// State of a toggle as reported by `EnumToggles::status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToggleStatus {
+    /// Name of the toggle.
+    pub name: String,
+    /// Current value.
+    pub value: bool,
+    /// Value restored by `reset`.
+    pub default: bool,
+    /// Origin of the current value.
+    pub provenance: Provenance,
+    /// Time of the last change of value, `None` if it never changed.
+    pub last_changed: Option<SystemTime>,
+    /// Documentation of the toggle.
+    pub metadata: ToggleMetadata,
+}
+
+/// Structured reporting of the toggle states.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Attach documentation to a toggle, reported by `status`.
+    ///
+    /// This operation is *O*(*1*).
+    pub fn describe(&mut self, toggle: T, metadata: ToggleMetadata) {
+        let toggle_id = self.toggle_id(&toggle);
+        self.metadata[toggle_id] = metadata;
+    }
+
+    /// Report the value, default, provenance, last change and metadata of every toggle.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn status(&self) -> Vec<ToggleStatus> {
+        T::iter()
+            .enumerate()
+            .map(|(toggle_id, toggle)| ToggleStatus {
+                name: toggle.as_ref().to_string(),
+                value: self.get(toggle_id),
+                default: self.defaults[toggle_id],
+                provenance: self.provenance[toggle_id].clone(),
+                last_changed: self.changed_at[toggle_id],
+                metadata: self.metadata[toggle_id].clone(),
+            })
+            .collect()
+    }
+
+    /// Render the value, default, description, owner and expiry of every toggle as a markdown
+    /// table, for flag-hygiene reviews.
+    ///
+    /// This operation is *O*(*n*).
+    pub fn report(&self) -> String {
+        let mut table = String::from(
+            "| Toggle | Value | Default | Description | Owner | Expires |\n\
+             |---|---|---|---|---|---|\n",
+        );
+        for status in self.status() {
+            table.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                cell(Some(&status.name)),
+                status.value,
+                status.default,
+                cell(status.metadata.description.as_deref()),
+                cell(status.metadata.owner.as_deref()),
+                cell(status.metadata.expires.as_deref()),
+            ));
+        }
+        table
+    }
+}
+
+/// Escape a markdown table cell, empty for `None`.
+fn cell(value: Option<&str>) -> String {
+    value
+        .unwrap_or_default()
+        .replace('|', "\\|")
+        .replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_status() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set_default(TestToggles::Toggle2 as usize, true);
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.describe(
+            TestToggles::Toggle1,
+            ToggleMetadata {
+                owner: Some("team-search".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let status = toggles.status();
+        assert_eq!(status.len(), 2);
+        assert_eq!(status[0].name, "Toggle1");
+        assert!(status[0].value);
+        assert_eq!(status[0].provenance, Provenance::Api);
+        assert!(status[0].last_changed.is_some());
+        assert_eq!(status[0].metadata.owner.as_deref(), Some("team-search"));
+        assert!(!status[1].value);
+        assert!(status[1].default);
+        assert_eq!(status[1].provenance, Provenance::Default);
+        assert_eq!(status[1].last_changed, None);
+
+        toggles.reset_all();
+        let status = toggles.status();
+        assert_eq!(status[0].provenance, Provenance::Default);
+        assert!(status[1].value);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.set(TestToggles::Toggle1 as usize, true);
+        toggles.describe(
+            TestToggles::Toggle2,
+            ToggleMetadata {
+                description: Some("Search | ranking\nv2".to_string()),
+                owner: Some("team-search".to_string()),
+                expires: Some("2025-12-31".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            toggles.report(),
+            "| Toggle | Value | Default | Description | Owner | Expires |\n\
+             |---|---|---|---|---|---|\n\
+             | Toggle1 | true | false |  |  |  |\n\
+             | Toggle2 | false | false | Search \\| ranking v2 | team-search | 2025-12-31 |\n"
+        );
+    }
+}
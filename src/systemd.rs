@@ -0,0 +1,120 @@
+use crate::{EnumToggles, ToggleError};
+use std::env;
+use std::io;
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Send a state string (`READY=1`, `STATUS=...`) to the service manager through
+/// `$NOTIFY_SOCKET`. Returns false without error when not running under systemd.
+pub fn sd_notify(state: &str) -> io::Result<bool> {
+    let path = match env::var_os("NOTIFY_SOCKET") {
+        Some(path) if !path.is_empty() => path,
+        _ => return Ok(false),
+    };
+    let path = path.to_string_lossy();
+    let address = match path.strip_prefix('@') {
+        Some(name) => SocketAddr::from_abstract_name(name.as_bytes())?,
+        None => SocketAddr::from_pathname(path.as_ref())?,
+    };
+    let socket = UnixDatagram::unbound()?;
+    socket.send_to_addr(state.as_bytes(), &address)?;
+    Ok(true)
+}
+
+/// Current `CLOCK_MONOTONIC` time in microseconds, required along with `RELOADING=1`.
+fn monotonic_usec() -> u64 {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `now` is a valid timespec and CLOCK_MONOTONIC is always supported on Linux.
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+    now.tv_sec as u64 * 1_000_000 + now.tv_nsec as u64 / 1_000
+}
+
+/// Reload requests sent the systemd way, `systemctl reload` delivering SIGHUP to services of
+/// `Type=notify-reload`.
+pub struct ReloadSignal {
+    requested: Arc<AtomicBool>,
+}
+
+impl ReloadSignal {
+    /// Start recording SIGHUP, replacing its default action of terminating the process.
+    pub fn install() -> io::Result<Self> {
+        let requested = Arc::new(AtomicBool::new(false));
+        signal_hook::flag::register(signal_hook::consts::SIGHUP, requested.clone())?;
+        Ok(ReloadSignal { requested })
+    }
+
+    /// True once per received SIGHUP, poll it from the application loop.
+    pub fn take(&self) -> bool {
+        self.requested.swap(false, Ordering::Relaxed)
+    }
+}
+
+/// Reloads reported to systemd.
+impl<T> EnumToggles<T>
+where
+    T: strum::IntoEnumIterator + AsRef<str> + PartialEq + 'static,
+{
+    /// Reload a yaml file, notifying systemd with `RELOADING=1` before and `READY=1` after,
+    /// along with a `STATUS=` line describing the outcome.
+    pub fn reload_with_sd_notify(&mut self, filepath: &str) -> Result<(), ToggleError> {
+        sd_notify(&format!(
+            "RELOADING=1\nMONOTONIC_USEC={}\nSTATUS=Reloading toggles from {}",
+            monotonic_usec(),
+            filepath
+        ))?;
+        let result = self.load_from_file(filepath).map(|_| ());
+        let status = match &result {
+            Ok(()) => format!("Toggles loaded, generation {}", self.generation()),
+            Err(e) => format!("Toggles reload failed, keeping previous values: {}", e),
+        };
+        sd_notify(&format!("READY=1\nSTATUS={}", status))?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use strum_macros::{AsRefStr, EnumIter};
+
+    #[derive(AsRefStr, EnumIter, PartialEq)]
+    pub enum TestToggles {
+        Toggle1,
+        Toggle2,
+    }
+
+    #[test]
+    fn test_reload_with_sd_notify() {
+        let dir = tempfile::tempdir().expect("Unable to create temporary directory");
+        let socket_path = dir.path().join("notify.sock");
+        let socket = UnixDatagram::bind(&socket_path).expect("Unable to bind socket");
+        env::set_var("NOTIFY_SOCKET", &socket_path);
+
+        let mut temp_file =
+            tempfile::NamedTempFile::new().expect("Unable to create temporary file");
+        writeln!(temp_file, "Toggle1: 1").expect("Unable to write to temporary file");
+        let filepath = temp_file.path().to_str().unwrap();
+
+        let mut toggles: EnumToggles<TestToggles> = EnumToggles::new();
+        toggles.reload_with_sd_notify(filepath).unwrap();
+        assert!(toggles.get(TestToggles::Toggle1 as usize));
+
+        let mut buffer = [0u8; 512];
+        let size = socket.recv(&mut buffer).unwrap();
+        assert!(
+            String::from_utf8_lossy(&buffer[..size]).starts_with("RELOADING=1\nMONOTONIC_USEC=")
+        );
+        let size = socket.recv(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&buffer[..size]),
+            "READY=1\nSTATUS=Toggles loaded, generation 1"
+        );
+        env::remove_var("NOTIFY_SOCKET");
+    }
+}
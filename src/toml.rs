@@ -0,0 +1,99 @@
+use crate::ToggleError;
+use toml::{Table, Value};
+use yaml_rust::Yaml;
+
+/// Parse the toggles defined in a TOML document, in document order. With `table`, e.g.
+/// `Some("toggles")`, only the keys of this table are read, so the toggles can live in the
+/// `[toggles]` section of a larger application config file.
+pub(crate) fn parse(
+    content: &str,
+    table: Option<&str>,
+) -> Result<Vec<(String, bool)>, ToggleError> {
+    parse_values(content, table)?
+        .into_iter()
+        .map(|(key, value)| {
+            let value = to_bool(&key, &value)?;
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Parse the keys of a TOML document like `parse`, keeping their values.
+pub(crate) fn parse_values(
+    content: &str,
+    table: Option<&str>,
+) -> Result<Vec<(String, Value)>, ToggleError> {
+    let document: Table = content
+        .parse()
+        .map_err(|e: toml::de::Error| ToggleError::Toml(e.message().to_string()))?;
+    let toggles = match table {
+        Some(name) => match document.get(name) {
+            Some(Value::Table(toggles)) => toggles,
+            Some(_) => {
+                return Err(ToggleError::TypeMismatch {
+                    key: format!("table {}", name),
+                    expected: "a table".to_string(),
+                })
+            }
+            None => return Ok(Vec::new()),
+        },
+        None => &document,
+    };
+    Ok(toggles
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect())
+}
+
+/// Convert a TOML value into a yaml node, a bad value for tables, arrays and dates.
+pub(crate) fn to_yaml(value: &Value) -> Yaml {
+    match value {
+        Value::Boolean(value) => Yaml::Boolean(*value),
+        Value::Integer(value) => Yaml::Integer(*value),
+        Value::Float(value) => Yaml::Real(value.to_string()),
+        Value::String(value) => Yaml::String(value.clone()),
+        _ => Yaml::BadValue,
+    }
+}
+
+fn to_bool(key: &str, value: &Value) -> Result<bool, ToggleError> {
+    match value {
+        Value::Boolean(value) => Ok(*value),
+        Value::Integer(1) => Ok(true),
+        Value::Integer(0) => Ok(false),
+        _ => Err(ToggleError::TypeMismatch {
+            key: format!("value of {}", key),
+            expected: "true, false, 0 or 1".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("FeatureB = true\nFeatureA = 0\n", None).unwrap(),
+            vec![
+                ("FeatureB".to_string(), true),
+                ("FeatureA".to_string(), false)
+            ]
+        );
+        let config = "name = \"app\"\n\n[toggles]\nFeatureA = true\n";
+        assert_eq!(
+            parse(config, Some("toggles")).unwrap(),
+            vec![("FeatureA".to_string(), true)]
+        );
+        assert!(parse(config, Some("flags")).unwrap().is_empty());
+        assert!(matches!(
+            parse(config, None),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            parse("FeatureA = ", None),
+            Err(ToggleError::Toml(_))
+        ));
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{naming, ToggleError};
+use std::collections::HashSet;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
+use yaml_rust::{Yaml, YamlLoader};
+
+/// Value of a toggle key: on or off, or a rollout percentage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Setting {
+    /// On or off, see `to_bool`.
+    Value(bool),
+    /// Percentage, written `37%` or `0.37`.
+    Rollout(f64),
+}
+
+/// Toggles of a yaml document, in document order.
+#[derive(Default)]
+pub(crate) struct Values {
+    /// Toggles set to a value or to a rollout percentage.
+    pub(crate) entries: Vec<(String, Setting)>,
+    /// Files listed by the top-level `include:` key, to be read before this document.
+    pub(crate) includes: Vec<String>,
+}
+
+/// Parse the toggles and rollout percentages defined in a yaml document. Nested maps are
+/// namespaces prefixing the names of their keys, `payments: { NewCheckout: 1 }` declaring
+/// `PaymentsNewCheckout`.
+pub(crate) fn parse_values(content: &str) -> Result<Values, ToggleError> {
+    let docs = YamlLoader::load_from_str(content)?;
+    let mut values = Values::default();
+    match docs.first() {
+        None | Some(Yaml::Null) => {}
+        Some(Yaml::Hash(h)) => flatten("", h, &mut values)?,
+        Some(_) => {
+            return Err(ToggleError::TypeMismatch {
+                key: "root document".to_string(),
+                expected: "a map".to_string(),
+            })
+        }
+    }
+    Ok(values)
+}
+
+/// Collect the values of a map whose keys are prefixed with `namespace`, descending into
+/// the nested maps.
+fn flatten(
+    namespace: &str,
+    map: &yaml_rust::yaml::Hash,
+    values: &mut Values,
+) -> Result<(), ToggleError> {
+    for (key, value) in map {
+        if namespace.is_empty() && key.as_str() == Some("include") {
+            values.includes = includes(value)?;
+        } else if let (Some(name), Yaml::Hash(nested)) = (key.as_str(), value) {
+            flatten(
+                &format!("{}{}", namespace, naming::capitalize(name)),
+                nested,
+                values,
+            )?;
+        } else if let (Some(name), Some(percentage)) = (key.as_str(), to_percentage(value)) {
+            values
+                .entries
+                .push((join(namespace, name), Setting::Rollout(percentage)));
+        } else {
+            let (name, value) = entry(key, value)?;
+            values
+                .entries
+                .push((join(namespace, &name), Setting::Value(value)));
+        }
+    }
+    Ok(())
+}
+
+/// Read the value of the `include:` key, one path or a list of paths.
+fn includes(value: &Yaml) -> Result<Vec<String>, ToggleError> {
+    let paths = match value {
+        Yaml::String(path) => return Ok(vec![path.clone()]),
+        Yaml::Array(paths) => paths
+            .iter()
+            .map(|path| path.as_str().map(str::to_string))
+            .collect(),
+        _ => None,
+    };
+    paths.ok_or_else(|| ToggleError::TypeMismatch {
+        key: "include".to_string(),
+        expected: "a path or a list of paths".to_string(),
+    })
+}
+
+/// Name of a key of the `namespace` map.
+fn join(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}{}", namespace, naming::capitalize(name))
+    }
+}
+
+/// Read a rollout percentage: a string such as `37%` or a real number such as `0.37`.
+fn to_percentage(value: &Yaml) -> Option<f64> {
+    let percentage = match value {
+        Yaml::Real(_) => value.as_f64()? * 100.0,
+        Yaml::String(s) => s.trim().strip_suffix('%')?.trim().parse().ok()?,
+        _ => return None,
+    };
+    (0.0..=100.0).contains(&percentage).then_some(percentage)
+}
+
+/// Read a toggle value: `0`/`1`, a yaml boolean, or one of the strings `true`/`false`,
+/// `yes`/`no`, `on`/`off`, `1`/`0` in any case.
+pub(crate) fn to_bool(value: &Yaml) -> Option<bool> {
+    match value {
+        Yaml::Integer(1) | Yaml::Boolean(true) => Some(true),
+        Yaml::Integer(0) | Yaml::Boolean(false) => Some(false),
+        Yaml::String(s) => naming::str_to_bool(s),
+        _ => None,
+    }
+}
+
+/// Convert the `(key, text)` pairs of a line-based format, see `naming::str_to_bool` for the
+/// accepted values. `prefix` is prepended to the keys in errors, e.g. the prefix of `.env` keys.
+pub(crate) fn text_entries(
+    pairs: Vec<(String, String)>,
+    prefix: &str,
+) -> Result<Vec<(String, bool)>, ToggleError> {
+    pairs
+        .into_iter()
+        .map(|(key, text)| {
+            let value = naming::str_to_bool(&text).ok_or_else(|| ToggleError::TypeMismatch {
+                key: format!("value of {}{}", prefix, key),
+                expected: "0, 1, true, false, yes, no, on or off".to_string(),
+            })?;
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Convert a `Name: value` pair of a yaml map, see `to_bool` for the accepted values.
+pub(crate) fn entry(key: &Yaml, value: &Yaml) -> Result<(String, bool), ToggleError> {
+    let key = key.as_str().ok_or_else(|| ToggleError::TypeMismatch {
+        key: format!("key {:?}", key),
+        expected: "a string".to_string(),
+    })?;
+    let value = to_bool(value).ok_or_else(|| ToggleError::TypeMismatch {
+        key: format!("value of {}", key),
+        expected: "0, 1, true, false, yes, no, on or off".to_string(),
+    })?;
+    Ok((key.to_string(), value))
+}
+
+/// Shape of a yaml document, read from the parser events without building the document.
+pub(crate) struct Shape {
+    /// Keys declared again later in the document, namespaces included, whose earlier values
+    /// the loader drops silently. A key declared three times is listed twice.
+    pub(crate) shadowed_keys: Vec<String>,
+    /// Number of keys of the document, duplicates and keys of nested maps included, the maps
+    /// themselves excluded.
+    pub(crate) key_count: usize,
+    /// Deepest nesting of maps and sequences, 1 for a flat map.
+    pub(crate) max_depth: usize,
+}
+
+/// Read the shape of a yaml document.
+pub(crate) fn shape(content: &str) -> Result<Shape, ToggleError> {
+    let mut collector = KeyCollector::default();
+    Parser::new(content.chars()).load(&mut collector, false)?;
+    let mut seen = HashSet::new();
+    let mut shadowed_keys: Vec<String> = collector
+        .keys
+        .into_iter()
+        .rev()
+        .filter(|key| !seen.insert(key.clone()))
+        .collect();
+    shadowed_keys.reverse();
+    Ok(Shape {
+        shadowed_keys,
+        key_count: collector.key_count,
+        max_depth: collector.max_depth,
+    })
+}
+
+/// A map or sequence being read.
+struct Frame {
+    /// Whether the frame is a map rather than a sequence.
+    map: bool,
+    /// Namespace of the keys of a map, `None` for sequences and the maps they hold.
+    namespace: Option<String>,
+    /// Whether the next node is a value, keys and values alternating in maps.
+    in_value: bool,
+    /// Key of the value being read.
+    key: Option<String>,
+}
+
+/// Collect the keys of the maps from the parser events, named like `flatten` names them.
+#[derive(Default)]
+struct KeyCollector {
+    frames: Vec<Frame>,
+    max_depth: usize,
+    key_count: usize,
+    keys: Vec<String>,
+}
+
+impl KeyCollector {
+    /// Namespace of a map starting at the current position, a value of a namespaced map.
+    fn nested_namespace(&self) -> Option<String> {
+        match self.frames.last() {
+            None => Some(String::new()),
+            Some(Frame {
+                namespace: Some(namespace),
+                in_value: true,
+                key: Some(key),
+                ..
+            }) => Some(format!("{}{}", namespace, naming::capitalize(key))),
+            Some(_) => None,
+        }
+    }
+
+    fn start(&mut self, map: bool, namespace: Option<String>) {
+        self.frames.push(Frame {
+            map,
+            namespace,
+            in_value: false,
+            key: None,
+        });
+        self.max_depth = self.max_depth.max(self.frames.len());
+    }
+
+    /// A node of the current map is complete, `scalar` holding its text. Nested maps are
+    /// namespaces rather than keys, every other key is counted.
+    fn node_done(&mut self, scalar: Option<String>, nested_map: bool) {
+        let top_level = self.frames.len() == 1;
+        let Some(frame) = self.frames.last_mut().filter(|frame| frame.map) else {
+            return;
+        };
+        if !frame.in_value {
+            frame.key = scalar;
+            frame.in_value = true;
+            return;
+        }
+        if !nested_map {
+            self.key_count += 1;
+        }
+        if let (Some(namespace), Some(key), false) = (&frame.namespace, &frame.key, nested_map) {
+            if !(top_level && key == "include") {
+                self.keys.push(join(namespace, key));
+            }
+        }
+        frame.key = None;
+        frame.in_value = false;
+    }
+}
+
+impl MarkedEventReceiver for KeyCollector {
+    fn on_event(&mut self, event: Event, _mark: Marker) {
+        match event {
+            Event::MappingStart(..) => {
+                let namespace = self.nested_namespace();
+                self.start(true, namespace);
+            }
+            Event::SequenceStart(..) => self.start(false, None),
+            Event::MappingEnd => {
+                self.frames.pop();
+                self.node_done(None, true);
+            }
+            Event::SequenceEnd => {
+                self.frames.pop();
+                self.node_done(None, false);
+            }
+            Event::Scalar(value, ..) => self.node_done(Some(value), false),
+            Event::Alias(_) => self.node_done(None, false),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_values() {
+        let values = parse_values("Toggle1: 37%\nToggle2: 0.5\nToggle3: 1\n").unwrap();
+        assert_eq!(
+            values.entries,
+            vec![
+                ("Toggle1".to_string(), Setting::Rollout(37.0)),
+                ("Toggle2".to_string(), Setting::Rollout(50.0)),
+                ("Toggle3".to_string(), Setting::Value(true))
+            ]
+        );
+        assert!(parse_values("Toggle1: 120%\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_includes() {
+        let values = parse_values("include: [base.yaml, team.yaml]\nToggle1: 1\n").unwrap();
+        assert_eq!(values.includes, vec!["base.yaml", "team.yaml"]);
+        assert_eq!(
+            values.entries,
+            vec![("Toggle1".to_string(), Setting::Value(true))]
+        );
+        assert_eq!(
+            parse_values("include: base.yaml\n").unwrap().includes,
+            vec!["base.yaml"]
+        );
+        assert!(parse_values("include: [1]\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_namespaces() {
+        let content = "toggle1: 1\npayments:\n  NewCheckout: 1\n  wallets:\n    applePay: 0\n";
+        assert_eq!(
+            parse_values(content).unwrap().entries,
+            vec![
+                ("toggle1".to_string(), Setting::Value(true)),
+                ("PaymentsNewCheckout".to_string(), Setting::Value(true)),
+                ("PaymentsWalletsApplePay".to_string(), Setting::Value(false))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shape() {
+        let content =
+            "FeatureA: 1\nNested:\n  FeatureA: 0\n  FeatureB: 0\nFeatureB: 1\nFeatureA: 0\n";
+        let document = shape(content).unwrap();
+        assert_eq!(document.shadowed_keys, vec!["FeatureA"]);
+        assert_eq!(document.key_count, 5);
+        assert_eq!(document.max_depth, 2);
+        assert!(shape("FeatureA: 1\nFeatureB: 0\n")
+            .unwrap()
+            .shadowed_keys
+            .is_empty());
+        assert_eq!(
+            shape("payments:\n  A: 1\n  A: 0\n  A: 1\ninclude: [a, b]\nB: [1]\nB: 0\n")
+                .unwrap()
+                .shadowed_keys,
+            vec!["PaymentsA", "PaymentsA", "B"]
+        );
+        assert_eq!(
+            parse_values("FeatureA: 1\nFeatureA: 0\n").unwrap().entries,
+            vec![("FeatureA".to_string(), Setting::Value(false))]
+        );
+        assert!(parse_values("").unwrap().entries.is_empty());
+        assert_eq!(
+            parse_values("A: true\nB: \"Off\"\nC: yes\nD: 0\n")
+                .unwrap()
+                .entries,
+            vec![
+                ("A".to_string(), Setting::Value(true)),
+                ("B".to_string(), Setting::Value(false)),
+                ("C".to_string(), Setting::Value(true)),
+                ("D".to_string(), Setting::Value(false))
+            ]
+        );
+        assert!(matches!(
+            parse_values("A: 2\n"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            parse_values("A: maybe\n"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            parse_values("- FeatureA\n"),
+            Err(ToggleError::TypeMismatch { .. })
+        ));
+    }
+}